@@ -1,11 +1,17 @@
 use std::env::args;
+use std::fs;
 use std::io::stderr;
 use std::io::stdout;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::exit;
 
 use airlang::cfg::CoreCfg;
 use airlang::semantics::core::Eval;
 use airlang::semantics::func::DynFunc;
 use airlang::semantics::val::Val;
+use airlang::syntax::FmtCtx;
+use airlang::syntax::cst::Cst;
 use airlang::type_::Text;
 
 use crate::cfg2::BinCfg2;
@@ -19,6 +25,10 @@ pub fn main() -> std::io::Result<()> {
     }
     let _ = args.next().unwrap();
     let path = args.next().unwrap();
+    // a script named fmt can still be run by a path like ./fmt
+    if path == "fmt" {
+        return fmt(args.collect());
+    }
     interpret_file(&path)
 }
 
@@ -40,6 +50,76 @@ pub fn interpret_file(path: &str) -> std::io::Result<()> {
     }
 }
 
+const FMT_EXT: &str = "air";
+
+const FMT_WIDTH: usize = 100;
+
+const FMT_USAGE: &str = "usage: airlang_bin fmt [--check] <path>...";
+
+// format air files and directories of air files in place,
+// with `--check`, list unformatted files instead and exit with a non-zero code
+pub fn fmt(args: Vec<String>) -> std::io::Result<()> {
+    use std::io::Write;
+    let check = args.iter().any(|arg| arg == "--check");
+    let paths: Vec<_> = args.iter().filter(|arg| *arg != "--check").collect();
+    if paths.is_empty() {
+        let mut lock = stderr().lock();
+        writeln!(lock, "{FMT_USAGE}")?;
+        exit(1);
+    }
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(Path::new(path), &mut files)?;
+    }
+    let ctx = FmtCtx::default().width(FMT_WIDTH);
+    let mut failed = false;
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        let cst = match Cst::parse(source) {
+            Ok(cst) => cst,
+            Err(e) => {
+                let mut lock = stderr().lock();
+                writeln!(lock, "{}\n{e}", file.display())?;
+                failed = true;
+                continue;
+            },
+        };
+        let formatted = format!("{}\n", cst.format(ctx));
+        if formatted == cst.source() {
+            continue;
+        }
+        if check {
+            let mut lock = stdout().lock();
+            writeln!(lock, "{}", file.display())?;
+            failed = true;
+        } else {
+            fs::write(&file, formatted)?;
+        }
+    }
+    if failed {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path)? {
+        entries.push(entry?.path());
+    }
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|ext| ext == FMT_EXT) {
+            collect_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
+
 // AIR CODE
 fn generate_load(path: &str) -> String {
     use std::fmt::Write;
@@ -436,8 +436,8 @@ impl<T: ReplTerminal> Repl<T> {
     }
 
     fn recover(&mut self) {
-        self.cfg.remove(&Key::from_str_unchecked(Cfg::ABORT_TYPE));
-        self.cfg.remove(&Key::from_str_unchecked(Cfg::ABORT_MSG));
+        self.cfg.swap_remove(&Key::from_str_unchecked(Cfg::ABORT_TYPE));
+        self.cfg.swap_remove(&Key::from_str_unchecked(Cfg::ABORT_MSG));
         self.cfg.recover();
     }
 
@@ -47,13 +47,13 @@ pub fn call(cfg: &mut Cfg, input: Val) -> Val {
     let Val::Map(mut map) = input else {
         return bug!(cfg, "{CALL}: expected input to be a map, but got {input}");
     };
-    let Some(program) = map.remove(&Key::from_str_unchecked(PROGRAM)) else {
+    let Some(program) = map.shift_remove(&Key::from_str_unchecked(PROGRAM)) else {
         return bug!(cfg, "{CALL}: {PROGRAM} not found");
     };
     let Val::Text(program) = program else {
         return bug!(cfg, "{CALL}: expected {PROGRAM} to be a text, but got {program}");
     };
-    let Some(arguments) = map.remove(&Key::from_str_unchecked(ARGUMENTS)) else {
+    let Some(arguments) = map.shift_remove(&Key::from_str_unchecked(ARGUMENTS)) else {
         return bug!(cfg, "{CALL}: {ARGUMENTS} not found");
     };
     let Val::List(arguments) = arguments else {
@@ -8,6 +8,7 @@ use self::ctx::CtxLib;
 use self::decimal::DecimalLib;
 use self::error::ErrorLib;
 use self::func::FuncLib;
+use self::hash::HashLib;
 use self::int::IntLib;
 use self::key::KeyLib;
 use self::lang::LangLib;
@@ -15,6 +16,8 @@ use self::link::LinkLib;
 use self::list::ListLib;
 use self::map::MapLib;
 use self::pair::PairLib;
+use self::rational::RationalLib;
+use self::regex::RegexLib;
 use self::resource::ResourceLib;
 use self::text::TextLib;
 use self::unit::UnitLib;
@@ -30,6 +33,7 @@ pub struct CoreLib {
     pub text: TextLib,
     pub int: IntLib,
     pub decimal: DecimalLib,
+    pub rational: RationalLib,
     pub byte: ByteLib,
     pub cell: CellLib,
     pub pair: PairLib,
@@ -45,6 +49,8 @@ pub struct CoreLib {
     pub resource: ResourceLib,
     pub error: ErrorLib,
     pub lang: LangLib,
+    pub regex: RegexLib,
+    pub hash: HashLib,
 }
 
 impl CfgMod for CoreLib {
@@ -55,6 +61,7 @@ impl CfgMod for CoreLib {
         self.text.extend(cfg);
         self.int.extend(cfg);
         self.decimal.extend(cfg);
+        self.rational.extend(cfg);
         self.byte.extend(cfg);
         self.cell.extend(cfg);
         self.pair.extend(cfg);
@@ -70,6 +77,8 @@ impl CfgMod for CoreLib {
         self.resource.extend(cfg);
         self.error.extend(cfg);
         self.lang.extend(cfg);
+        self.regex.extend(cfg);
+        self.hash.extend(cfg);
     }
 }
 
@@ -85,6 +94,8 @@ pub mod int;
 
 pub mod decimal;
 
+pub mod rational;
+
 pub mod byte;
 
 pub mod cell;
@@ -116,3 +127,7 @@ pub mod resource;
 pub mod error;
 
 pub mod lang;
+
+pub mod regex;
+
+pub mod hash;
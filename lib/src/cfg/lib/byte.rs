@@ -1,10 +1,17 @@
 use const_format::concatcp;
+use num_bigint::BigInt;
+use num_bigint::Sign;
+use num_traits::Signed;
+use num_traits::ToPrimitive;
 
 use crate::bug;
 use crate::cfg::CfgMod;
 use crate::cfg::extend_func;
+use crate::cfg::lib::list::to_index;
+use crate::cfg::lib::list::to_range;
 use crate::semantics::cfg::Cfg;
 use crate::semantics::core::PREFIX_ID;
+use crate::semantics::func::CtxConstInputEvalFunc;
 use crate::semantics::func::CtxConstInputFreeFunc;
 use crate::semantics::func::CtxFreeInputEvalFunc;
 use crate::semantics::func::CtxMutInputEvalFunc;
@@ -14,6 +21,11 @@ use crate::semantics::val::Val;
 use crate::type_::Byte;
 use crate::type_::Int;
 use crate::type_::Pair;
+use crate::type_::Text;
+use crate::utils::conversion::base64_str_to_vec_u8;
+use crate::utils::conversion::hex_str_to_vec_u8;
+use crate::utils::conversion::u8_array_to_base64_string;
+use crate::utils::conversion::u8_array_to_hex_string;
 
 // todo design add more
 #[derive(Clone)]
@@ -21,11 +33,34 @@ pub struct ByteLib {
     pub get_length: PrimFuncVal,
     pub push: PrimFuncVal,
     pub join: PrimFuncVal,
+    pub get: PrimFuncVal,
+    pub set: PrimFuncVal,
+    pub slice: PrimFuncVal,
+    pub find: PrimFuncVal,
+    pub into_hex: PrimFuncVal,
+    pub from_hex: PrimFuncVal,
+    pub into_base64: PrimFuncVal,
+    pub from_base64: PrimFuncVal,
+    pub from_integer: PrimFuncVal,
+    pub into_integer: PrimFuncVal,
 }
 
 pub const GET_LENGTH: &str = concatcp!(PREFIX_ID, BYTE, ".get_length");
 pub const PUSH: &str = concatcp!(PREFIX_ID, BYTE, ".push");
 pub const JOIN: &str = concatcp!(PREFIX_ID, BYTE, ".join");
+pub const GET: &str = concatcp!(PREFIX_ID, BYTE, ".get");
+pub const SET: &str = concatcp!(PREFIX_ID, BYTE, ".set");
+pub const SLICE: &str = concatcp!(PREFIX_ID, BYTE, ".slice");
+pub const FIND: &str = concatcp!(PREFIX_ID, BYTE, ".find");
+pub const INTO_HEX: &str = concatcp!(PREFIX_ID, BYTE, ".into_hex");
+pub const FROM_HEX: &str = concatcp!(PREFIX_ID, BYTE, ".from_hex");
+pub const INTO_BASE64: &str = concatcp!(PREFIX_ID, BYTE, ".into_base64");
+pub const FROM_BASE64: &str = concatcp!(PREFIX_ID, BYTE, ".from_base64");
+pub const FROM_INTEGER: &str = concatcp!(PREFIX_ID, BYTE, ".from_integer");
+pub const INTO_INTEGER: &str = concatcp!(PREFIX_ID, BYTE, ".into_integer");
+
+// the most bytes from_integer pads an integer with
+const MAX_PADDING: usize = 1 << 16;
 
 impl Default for ByteLib {
     fn default() -> Self {
@@ -33,6 +68,16 @@ impl Default for ByteLib {
             get_length: CtxConstInputFreeFunc { fn_: get_length }.build(),
             push: CtxMutInputEvalFunc { fn_: push }.build(),
             join: CtxFreeInputEvalFunc { fn_: join }.build(),
+            get: CtxConstInputEvalFunc { fn_: get }.build(),
+            set: CtxMutInputEvalFunc { fn_: set }.build(),
+            slice: CtxConstInputEvalFunc { fn_: slice }.build(),
+            find: CtxConstInputEvalFunc { fn_: find }.build(),
+            into_hex: CtxFreeInputEvalFunc { fn_: into_hex }.build(),
+            from_hex: CtxFreeInputEvalFunc { fn_: from_hex }.build(),
+            into_base64: CtxFreeInputEvalFunc { fn_: into_base64 }.build(),
+            from_base64: CtxFreeInputEvalFunc { fn_: from_base64 }.build(),
+            from_integer: CtxFreeInputEvalFunc { fn_: from_integer }.build(),
+            into_integer: CtxFreeInputEvalFunc { fn_: into_integer }.build(),
         }
     }
 }
@@ -42,6 +87,16 @@ impl CfgMod for ByteLib {
         extend_func(cfg, GET_LENGTH, self.get_length);
         extend_func(cfg, PUSH, self.push);
         extend_func(cfg, JOIN, self.join);
+        extend_func(cfg, GET, self.get);
+        extend_func(cfg, SET, self.set);
+        extend_func(cfg, SLICE, self.slice);
+        extend_func(cfg, FIND, self.find);
+        extend_func(cfg, INTO_HEX, self.into_hex);
+        extend_func(cfg, FROM_HEX, self.from_hex);
+        extend_func(cfg, INTO_BASE64, self.into_base64);
+        extend_func(cfg, FROM_BASE64, self.from_base64);
+        extend_func(cfg, FROM_INTEGER, self.from_integer);
+        extend_func(cfg, INTO_INTEGER, self.into_integer);
     }
 }
 
@@ -86,3 +141,227 @@ pub fn join(cfg: &mut Cfg, input: Val) -> Val {
     let byte = to_join.join(&**separator);
     Val::Byte(Byte::from(byte).into())
 }
+
+pub fn get(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Byte(byte) = ctx else {
+        return bug!(cfg, "{GET}: expected context to be a byte, but got {ctx}");
+    };
+    let Some(i) = to_index(cfg, GET, input) else {
+        return Val::default();
+    };
+    let Some(b) = byte.get(i) else {
+        return bug!(cfg, "{GET}: index {i} should < byte.len {}", byte.len());
+    };
+    Val::Int(Int::from(*b).into())
+}
+
+// index : value, returns the old value
+pub fn set(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Val::Byte(byte) = ctx else {
+        return bug!(cfg, "{SET}: expected context to be a byte, but got {ctx}");
+    };
+    let Val::Pair(index_value) = input else {
+        return bug!(cfg, "{SET}: expected input to be a pair, but got {input}");
+    };
+    let index_value = Pair::from(index_value);
+    let Some(i) = to_index(cfg, SET, index_value.left) else {
+        return Val::default();
+    };
+    let Val::Int(value) = index_value.right else {
+        return bug!(
+            cfg,
+            "{SET}: expected input.right to be an integer, but got {}",
+            index_value.right
+        );
+    };
+    let Some(value) = value.to_u8() else {
+        return bug!(cfg, "{SET}: expected input.right to be in 0 : 255, but got {value}");
+    };
+    let len = byte.len();
+    let Some(current) = byte.get_mut(i) else {
+        return bug!(cfg, "{SET}: index {i} should < byte.len {len}");
+    };
+    let old = std::mem::replace(current, value);
+    Val::Int(Int::from(old).into())
+}
+
+pub fn slice(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Byte(byte) = ctx else {
+        return bug!(cfg, "{SLICE}: expected context to be a byte, but got {ctx}");
+    };
+    let Val::Pair(range) = input else {
+        return bug!(cfg, "{SLICE}: expected input to be a pair, but got {input}");
+    };
+    let range = Pair::from(range);
+    let Some((from, to)) = to_range(cfg, SLICE, range) else {
+        return Val::default();
+    };
+    let from = from.unwrap_or_default();
+    let to = to.unwrap_or(byte.len());
+    let Some(slice) = byte.get(from .. to) else {
+        return bug!(cfg, "{SLICE}: range {from} : {to} should be in 0 : {}", byte.len());
+    };
+    Val::Byte(Byte::from(slice.to_owned()).into())
+}
+
+// index of the first match
+pub fn find(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Byte(byte) = ctx else {
+        return bug!(cfg, "{FIND}: expected context to be a byte, but got {ctx}");
+    };
+    let Val::Byte(pattern) = input else {
+        return bug!(cfg, "{FIND}: expected input to be a byte, but got {input}");
+    };
+    let index = if pattern.is_empty() {
+        Some(0)
+    } else {
+        byte.windows(pattern.len()).position(|window| window == &**pattern)
+    };
+    let Some(index) = index else {
+        return Val::default();
+    };
+    Val::Int(Int::from(index).into())
+}
+
+pub fn into_hex(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Byte(byte) = input else {
+        return bug!(cfg, "{INTO_HEX}: expected input to be a byte, but got {input}");
+    };
+    Val::Text(Text::from(u8_array_to_hex_string(&byte)).into())
+}
+
+pub fn from_hex(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{FROM_HEX}: expected input to be a text, but got {input}");
+    };
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Val::default();
+    }
+    let Ok(byte) = hex_str_to_vec_u8(&text) else {
+        return Val::default();
+    };
+    Val::Byte(Byte::from(byte).into())
+}
+
+pub fn into_base64(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Byte(byte) = input else {
+        return bug!(cfg, "{INTO_BASE64}: expected input to be a byte, but got {input}");
+    };
+    Val::Text(Text::from(u8_array_to_base64_string(&byte)).into())
+}
+
+pub fn from_base64(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{FROM_BASE64}: expected input to be a text, but got {input}");
+    };
+    let Some(byte) = base64_str_to_vec_u8(&text) else {
+        return Val::default();
+    };
+    Val::Byte(Byte::from(byte).into())
+}
+
+const ENDIAN_BIG: &str = concatcp!(PREFIX_ID, "big");
+const ENDIAN_LITTLE: &str = concatcp!(PREFIX_ID, "little");
+
+// integer : width : endian, negative integers in two's complement
+pub fn from_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{FROM_INTEGER}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Int(int) = pair.left else {
+        return bug!(
+            cfg,
+            "{FROM_INTEGER}: expected input.left to be an integer, but got {}",
+            pair.left
+        );
+    };
+    let Val::Pair(width_endian) = pair.right else {
+        return bug!(
+            cfg,
+            "{FROM_INTEGER}: expected input.right to be a pair, but got {}",
+            pair.right
+        );
+    };
+    let width_endian = Pair::from(width_endian);
+    let Some(width) = to_index(cfg, FROM_INTEGER, width_endian.left) else {
+        return Val::default();
+    };
+    let Some(big) = to_endian(cfg, FROM_INTEGER, width_endian.right) else {
+        return Val::default();
+    };
+    let (mut bytes, fill) = if int.is_negative() {
+        (int.to_signed_bytes_le(), 0xff)
+    } else {
+        (int.magnitude().to_bytes_le(), 0)
+    };
+    if int.sign() == Sign::NoSign {
+        bytes.clear();
+    }
+    if bytes.len() > width {
+        return bug!(cfg, "{FROM_INTEGER}: integer {int} doesn't fit in {width} bytes");
+    }
+    if width - bytes.len() > MAX_PADDING {
+        let max = bytes.len() + MAX_PADDING;
+        return bug!(cfg, "{FROM_INTEGER}: width {width} should <= {max}");
+    }
+    bytes.resize(width, fill);
+    if big {
+        bytes.reverse();
+    }
+    Val::Byte(Byte::from(bytes).into())
+}
+
+// byte : endian : signed
+pub fn into_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{INTO_INTEGER}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Byte(byte) = pair.left else {
+        return bug!(cfg, "{INTO_INTEGER}: expected input.left to be a byte, but got {}", pair.left);
+    };
+    let Val::Pair(endian_signed) = pair.right else {
+        return bug!(
+            cfg,
+            "{INTO_INTEGER}: expected input.right to be a pair, but got {}",
+            pair.right
+        );
+    };
+    let endian_signed = Pair::from(endian_signed);
+    let Some(big) = to_endian(cfg, INTO_INTEGER, endian_signed.left) else {
+        return Val::default();
+    };
+    let Val::Bit(signed) = endian_signed.right else {
+        return bug!(
+            cfg,
+            "{INTO_INTEGER}: expected signedness to be a bit, but got {}",
+            endian_signed.right
+        );
+    };
+    let int = match (big, *signed) {
+        (true, true) => BigInt::from_signed_bytes_be(&byte),
+        (true, false) => BigInt::from_bytes_be(Sign::Plus, &byte),
+        (false, true) => BigInt::from_signed_bytes_le(&byte),
+        (false, false) => BigInt::from_bytes_le(Sign::Plus, &byte),
+    };
+    Val::Int(Int::from(int).into())
+}
+
+fn to_endian(cfg: &mut Cfg, key: &str, val: Val) -> Option<bool> {
+    let Val::Key(endian) = val else {
+        bug!(cfg, "{key}: expected endian to be a key, but got {val}");
+        return None;
+    };
+    match &*endian {
+        ENDIAN_BIG => Some(true),
+        ENDIAN_LITTLE => Some(false),
+        _ => {
+            bug!(
+                cfg,
+                "{key}: expected endian to be {ENDIAN_BIG} or {ENDIAN_LITTLE}, but got {endian}"
+            );
+            None
+        },
+    }
+}
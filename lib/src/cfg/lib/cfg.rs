@@ -148,7 +148,7 @@ pub fn with(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
         if let Some(v) = v {
             cfg.insert(k, v);
         } else {
-            cfg.remove(&k);
+            cfg.swap_remove(&k);
         }
     }
     output
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use const_format::concatcp;
+use num_traits::ToPrimitive;
 
 use self::pattern::PatternAssign;
 use self::pattern::PatternMatch;
@@ -21,6 +22,7 @@ use crate::semantics::func::PrimFunc;
 use crate::semantics::func::PrimInput;
 use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
+use crate::type_::List;
 use crate::type_::Pair;
 
 #[derive(Clone)]
@@ -32,6 +34,10 @@ pub struct CtxLib {
     pub get_self: PrimFuncVal,
     // todo rename
     pub which: PrimFuncVal,
+    pub get_path: PrimFuncVal,
+    pub set_path: PrimFuncVal,
+    pub update_path: PrimFuncVal,
+    pub remove_path: PrimFuncVal,
 }
 
 const CTX: &str = "context";
@@ -42,6 +48,10 @@ pub const FORM: &str = concatcp!(PREFIX_ID, CTX, ".form");
 pub const REPRESENT: &str = concatcp!(PREFIX_ID, CTX, ".represent");
 pub const GET_SELF: &str = concatcp!(PREFIX_ID, CTX, ".get_self");
 pub const WHICH: &str = concatcp!(PREFIX_ID, CTX, ".which");
+pub const GET_PATH: &str = concatcp!(PREFIX_ID, CTX, ".get_path");
+pub const SET_PATH: &str = concatcp!(PREFIX_ID, CTX, ".set_path");
+pub const UPDATE_PATH: &str = concatcp!(PREFIX_ID, CTX, ".update_path");
+pub const REMOVE_PATH: &str = concatcp!(PREFIX_ID, CTX, ".remove_path");
 
 impl Default for CtxLib {
     fn default() -> Self {
@@ -53,6 +63,10 @@ impl Default for CtxLib {
             represent: CtxMutInputEvalFunc { fn_: represent }.build(),
             get_self: CtxConstInputFreeFunc { fn_: get_self }.build(),
             which: CtxMutInputEvalFunc { fn_: which }.build(),
+            get_path: CtxConstInputEvalFunc { fn_: get_path }.build(),
+            set_path: CtxMutInputEvalFunc { fn_: set_path }.build(),
+            update_path: CtxMutInputEvalFunc { fn_: update_path }.build(),
+            remove_path: CtxMutInputEvalFunc { fn_: remove_path }.build(),
         }
     }
 }
@@ -65,6 +79,10 @@ impl CfgMod for CtxLib {
         extend_func(cfg, REPRESENT, self.represent);
         extend_func(cfg, GET_SELF, self.get_self);
         extend_func(cfg, WHICH, self.which);
+        extend_func(cfg, GET_PATH, self.get_path);
+        extend_func(cfg, SET_PATH, self.set_path);
+        extend_func(cfg, UPDATE_PATH, self.update_path);
+        extend_func(cfg, REMOVE_PATH, self.remove_path);
     }
 }
 
@@ -123,4 +141,116 @@ pub fn which(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
     func.call(cfg, ctx, func_input.right)
 }
 
+// a path is a list of keys which are accepted by get, like map keys, list indexes and _left
+pub fn get_path(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(path) = to_path(cfg, GET_PATH, input) else {
+        return Val::default();
+    };
+    let Some(val) = ref_path(cfg, ctx, &path) else {
+        return Val::default();
+    };
+    val.clone()
+}
+
+// the last step is set like set, an empty path replaces the whole context
+pub fn set_path(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{SET_PATH}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Some(path) = to_path(cfg, SET_PATH, pair.left) else {
+        return Val::default();
+    };
+    set_val(cfg, ctx, path, pair.right);
+    Val::default()
+}
+
+// the function is called in the current context with the old value and returns the new value
+pub fn update_path(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{UPDATE_PATH}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Some(path) = to_path(cfg, UPDATE_PATH, pair.left) else {
+        return Val::default();
+    };
+    let Val::Func(func) = pair.right else {
+        return bug!(cfg, "{UPDATE_PATH}: expected input.right to be a function, \
+            but got {}", pair.right);
+    };
+    let Some(old) = ref_path(cfg, ctx, &path) else {
+        return Val::default();
+    };
+    let old = old.clone();
+    let new = func.call(cfg, ctx, old);
+    if cfg.is_aborted() {
+        return Val::default();
+    }
+    set_val(cfg, ctx, path, new);
+    Val::default()
+}
+
+// only map entries and list items can be removed, returns the removed value
+pub fn remove_path(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some(mut path) = to_path(cfg, REMOVE_PATH, input) else {
+        return Val::default();
+    };
+    let Some(last) = path.pop() else {
+        return bug!(cfg, "{REMOVE_PATH}: expected path to be non-empty");
+    };
+    let Some(parent) = ref_path_mut(cfg, ctx, path) else {
+        return Val::default();
+    };
+    match (parent, last) {
+        (Val::Map(map), Val::Key(key)) => {
+            let Some(value) = map.shift_remove(&key) else {
+                return bug!(cfg, "{REMOVE_PATH}: value not found for key {key} in the map");
+            };
+            value
+        },
+        (Val::List(list), Val::Int(i)) => {
+            let Some(i) = i.to_usize().filter(|i| *i < list.len()) else {
+                return bug!(cfg, "{REMOVE_PATH}: index {i} should < list.len {}", list.len());
+            };
+            list.remove(i)
+        },
+        (parent, last) => bug!(cfg, "{REMOVE_PATH}: unable to remove {last} from {parent}"),
+    }
+}
+
+fn to_path(cfg: &mut Cfg, key: &str, input: Val) -> Option<Vec<Val>> {
+    let Val::List(path) = input else {
+        bug!(cfg, "{key}: expected path to be a list, but got {input}");
+        return None;
+    };
+    Some(List::from(path).into())
+}
+
+fn ref_path<'a>(cfg: &mut Cfg, ctx: &'a Val, path: &[Val]) -> Option<&'a Val> {
+    let mut val = ctx;
+    for step in path {
+        val = val.ref_(cfg, step.clone())?;
+    }
+    Some(val)
+}
+
+fn ref_path_mut<'a>(cfg: &mut Cfg, ctx: &'a mut Val, path: Vec<Val>) -> Option<&'a mut Val> {
+    let mut val = ctx;
+    for step in path {
+        val = val.ref_mut(cfg, step)?;
+    }
+    Some(val)
+}
+
+fn set_val(cfg: &mut Cfg, ctx: &mut Val, mut path: Vec<Val>, value: Val) {
+    let Some(last) = path.pop() else {
+        *ctx = value;
+        return;
+    };
+    let Some(parent) = ref_path_mut(cfg, ctx, path) else {
+        return;
+    };
+    parent.set(cfg, last, value);
+}
+
 pub(in crate::cfg) mod pattern;
@@ -326,7 +326,7 @@ impl PatternAssign<Val, Val> for Map<Key, Pattern> {
             return None;
         };
         for (k, pattern) in self {
-            let Some(val) = val.remove(&k) else {
+            let Some(val) = val.shift_remove(&k) else {
                 bug!(cfg, "{tag}: value not found for key {k} in map {val}");
                 return None;
             };
@@ -2,10 +2,12 @@ use std::num::NonZeroU64;
 
 use const_format::concatcp;
 use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 
 use crate::bug;
 use crate::cfg::CfgMod;
 use crate::cfg::extend_func;
+use crate::cfg::lib::int::MAX_BITS;
 use crate::semantics::cfg::Cfg;
 use crate::semantics::core::PREFIX_ID;
 use crate::semantics::func::CtxFreeInputEvalFunc;
@@ -14,6 +16,7 @@ use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
 use crate::type_::Decimal;
 use crate::type_::DecimalConfig;
+use crate::type_::Int;
 use crate::type_::Key;
 use crate::type_::Pair;
 use crate::type_::RoundingMode;
@@ -30,6 +33,17 @@ pub struct DecimalLib {
     pub greater: PrimFuncVal,
     pub greater_equal: PrimFuncVal,
     pub less_greater: PrimFuncVal,
+    pub square_root: PrimFuncVal,
+    pub exp: PrimFuncVal,
+    pub ln: PrimFuncVal,
+    pub power: PrimFuncVal,
+    pub round: PrimFuncVal,
+    pub floor: PrimFuncVal,
+    pub ceil: PrimFuncVal,
+    pub truncate: PrimFuncVal,
+    pub abs: PrimFuncVal,
+    pub from_integer: PrimFuncVal,
+    pub into_integer: PrimFuncVal,
 }
 
 pub const ADD: &str = concatcp!(PREFIX_ID, DECIMAL, ".add");
@@ -41,6 +55,17 @@ pub const LESS_EQUAL: &str = concatcp!(PREFIX_ID, DECIMAL, ".less_equal");
 pub const GREATER: &str = concatcp!(PREFIX_ID, DECIMAL, ".greater");
 pub const GREATER_EQUAL: &str = concatcp!(PREFIX_ID, DECIMAL, ".greater_equal");
 pub const LESS_GREATER: &str = concatcp!(PREFIX_ID, DECIMAL, ".less_greater");
+pub const SQUARE_ROOT: &str = concatcp!(PREFIX_ID, DECIMAL, ".square_root");
+pub const EXP: &str = concatcp!(PREFIX_ID, DECIMAL, ".exp");
+pub const LN: &str = concatcp!(PREFIX_ID, DECIMAL, ".ln");
+pub const POWER: &str = concatcp!(PREFIX_ID, DECIMAL, ".power");
+pub const ROUND: &str = concatcp!(PREFIX_ID, DECIMAL, ".round");
+pub const FLOOR: &str = concatcp!(PREFIX_ID, DECIMAL, ".floor");
+pub const CEIL: &str = concatcp!(PREFIX_ID, DECIMAL, ".ceil");
+pub const TRUNCATE: &str = concatcp!(PREFIX_ID, DECIMAL, ".truncate");
+pub const ABS: &str = concatcp!(PREFIX_ID, DECIMAL, ".abs");
+pub const FROM_INTEGER: &str = concatcp!(PREFIX_ID, DECIMAL, ".from_integer");
+pub const INTO_INTEGER: &str = concatcp!(PREFIX_ID, DECIMAL, ".into_integer");
 
 impl Default for DecimalLib {
     fn default() -> Self {
@@ -54,6 +79,17 @@ impl Default for DecimalLib {
             greater: CtxFreeInputEvalFunc { fn_: greater }.build(),
             greater_equal: CtxFreeInputEvalFunc { fn_: greater_equal }.build(),
             less_greater: CtxFreeInputEvalFunc { fn_: less_greater }.build(),
+            square_root: CtxFreeInputEvalFunc { fn_: square_root }.build(),
+            exp: CtxFreeInputEvalFunc { fn_: exp }.build(),
+            ln: CtxFreeInputEvalFunc { fn_: ln }.build(),
+            power: CtxFreeInputEvalFunc { fn_: power }.build(),
+            round: CtxFreeInputEvalFunc { fn_: round }.build(),
+            floor: CtxFreeInputEvalFunc { fn_: floor }.build(),
+            ceil: CtxFreeInputEvalFunc { fn_: ceil }.build(),
+            truncate: CtxFreeInputEvalFunc { fn_: truncate }.build(),
+            abs: CtxFreeInputEvalFunc { fn_: abs }.build(),
+            from_integer: CtxFreeInputEvalFunc { fn_: from_integer }.build(),
+            into_integer: CtxFreeInputEvalFunc { fn_: into_integer }.build(),
         }
     }
 }
@@ -69,13 +105,24 @@ impl CfgMod for DecimalLib {
         extend_func(cfg, GREATER, self.greater);
         extend_func(cfg, GREATER_EQUAL, self.greater_equal);
         extend_func(cfg, LESS_GREATER, self.less_greater);
+        extend_func(cfg, SQUARE_ROOT, self.square_root);
+        extend_func(cfg, EXP, self.exp);
+        extend_func(cfg, LN, self.ln);
+        extend_func(cfg, POWER, self.power);
+        extend_func(cfg, ROUND, self.round);
+        extend_func(cfg, FLOOR, self.floor);
+        extend_func(cfg, CEIL, self.ceil);
+        extend_func(cfg, TRUNCATE, self.truncate);
+        extend_func(cfg, ABS, self.abs);
+        extend_func(cfg, FROM_INTEGER, self.from_integer);
+        extend_func(cfg, INTO_INTEGER, self.into_integer);
     }
 }
 
 pub const ROUNDING_MODE: &str = "_decimal.rounding.mode";
 pub const ROUNDING_PRECISION: &str = "_decimal.rounding.precision";
 
-fn decimal_config(cfg: &mut Cfg, tag: &str) -> Option<DecimalConfig> {
+pub(crate) fn decimal_config(cfg: &mut Cfg, tag: &str) -> Option<DecimalConfig> {
     let Some(mode) = cfg.import(Key::from_str_unchecked(ROUNDING_MODE)) else {
         bug!(cfg, "{tag}: config {ROUNDING_MODE} not found");
         return None;
@@ -208,6 +255,150 @@ pub fn less_greater(cfg: &mut Cfg, input: Val) -> Val {
     Val::Bit(d1.less_greater(&d2))
 }
 
+pub fn square_root(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{SQUARE_ROOT}: expected input to be a decimal, but got {input}");
+    };
+    let Some(config) = decimal_config(cfg, SQUARE_ROOT) else {
+        return Val::default();
+    };
+    let Some(d) = d.square_root(config) else {
+        return bug!(cfg, "{SQUARE_ROOT}: expected input to be non-negative, but got {d}");
+    };
+    Val::Decimal(d.into())
+}
+
+pub fn exp(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{EXP}: expected input to be a decimal, but got {input}");
+    };
+    let Some(config) = decimal_config(cfg, EXP) else {
+        return Val::default();
+    };
+    let Some(output) = d.exp(config) else {
+        return bug!(cfg, "{EXP}: exp of {d} is out of range");
+    };
+    Val::Decimal(output.into())
+}
+
+pub fn ln(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{LN}: expected input to be a decimal, but got {input}");
+    };
+    let Some(config) = decimal_config(cfg, LN) else {
+        return Val::default();
+    };
+    let Some(d) = d.ln(config) else {
+        return bug!(cfg, "{LN}: expected input to be positive, but got {d}");
+    };
+    Val::Decimal(d.into())
+}
+
+pub fn power(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((d1, d2)) = decimal_pair(cfg, POWER, input) else {
+        return Val::default();
+    };
+    let Some(config) = decimal_config(cfg, POWER) else {
+        return Val::default();
+    };
+    let Some(d) = d1.power(&d2, config) else {
+        return bug!(cfg, "{POWER}: power of {d1} and {d2} is undefined or too large");
+    };
+    Val::Decimal(d.into())
+}
+
+// decimal : scale, rounding by the configured mode
+pub fn round(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((d, scale, config)) = decimal_scale(cfg, ROUND, input) else {
+        return Val::default();
+    };
+    Val::Decimal(d.round(scale, config.rounding_mode()).into())
+}
+
+pub fn floor(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((d, scale, _)) = decimal_scale(cfg, FLOOR, input) else {
+        return Val::default();
+    };
+    Val::Decimal(d.round(scale, RoundingMode::Negative).into())
+}
+
+pub fn ceil(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((d, scale, _)) = decimal_scale(cfg, CEIL, input) else {
+        return Val::default();
+    };
+    Val::Decimal(d.round(scale, RoundingMode::Positive).into())
+}
+
+pub fn truncate(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((d, scale, _)) = decimal_scale(cfg, TRUNCATE, input) else {
+        return Val::default();
+    };
+    Val::Decimal(d.round(scale, RoundingMode::Zero).into())
+}
+
+pub fn abs(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{ABS}: expected input to be a decimal, but got {input}");
+    };
+    Val::Decimal(d.abs().into())
+}
+
+pub fn from_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Int(i) = input else {
+        return bug!(cfg, "{FROM_INTEGER}: expected input to be an integer, but got {input}");
+    };
+    let Some(config) = decimal_config(cfg, FROM_INTEGER) else {
+        return Val::default();
+    };
+    Val::Decimal(Decimal::from_int(Int::from(i), config).into())
+}
+
+pub fn into_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{INTO_INTEGER}: expected input to be a decimal, but got {input}");
+    };
+    let Some(config) = decimal_config(cfg, INTO_INTEGER) else {
+        return Val::default();
+    };
+    let Some(i) = d.to_int(config.rounding_mode(), MAX_BITS) else {
+        return bug!(cfg, "{INTO_INTEGER}: expected the result to have at most {MAX_BITS} bits");
+    };
+    Val::Int(i.into())
+}
+
+// the scale should be within the configured precision in either direction
+fn decimal_scale(
+    cfg: &mut Cfg, tag: &str, input: Val,
+) -> Option<(Decimal, i64, DecimalConfig)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::Decimal(d) = pair.left else {
+        bug!(cfg, "{tag}: expected input.left to be a decimal, but got {}", pair.left);
+        return None;
+    };
+    let Val::Int(scale) = pair.right else {
+        bug!(cfg, "{tag}: expected input.right to be an integer, but got {}", pair.right);
+        return None;
+    };
+    let Some(scale) = scale.to_i64() else {
+        bug!(cfg, "{tag}: scale is too large to be supported, got {scale}");
+        return None;
+    };
+    let config = decimal_config(cfg, tag)?;
+    let precision = config.precision().get();
+    if scale.unsigned_abs() > precision {
+        bug!(
+            cfg,
+            "{tag}: scale should be in the range of -{precision} to {precision}, got {scale}"
+        );
+        return None;
+    }
+    Some((d.into(), scale, config))
+}
+
 fn decimal_pair(cfg: &mut Cfg, tag: &str, input: Val) -> Option<(Decimal, Decimal)> {
     let Val::Pair(pair) = input else {
         bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
@@ -0,0 +1,148 @@
+use const_format::concatcp;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::bug;
+use crate::cfg::CfgMod;
+use crate::cfg::extend_func;
+use crate::semantics::cfg::Cfg;
+use crate::semantics::core::PREFIX_ID;
+use crate::semantics::func::CtxFreeInputEvalFunc;
+use crate::semantics::val::PrimFuncVal;
+use crate::semantics::val::Val;
+use crate::syntax::repr::Repr;
+use crate::type_::Byte;
+
+#[derive(Clone)]
+pub struct HashLib {
+    pub sha256: PrimFuncVal,
+    pub blake3: PrimFuncVal,
+    pub value: PrimFuncVal,
+}
+
+const HASH: &str = "hash";
+
+pub const SHA256: &str = concatcp!(PREFIX_ID, HASH, ".sha256");
+pub const BLAKE3: &str = concatcp!(PREFIX_ID, HASH, ".blake3");
+pub const VALUE: &str = concatcp!(PREFIX_ID, HASH, ".value");
+
+impl Default for HashLib {
+    fn default() -> Self {
+        HashLib {
+            sha256: CtxFreeInputEvalFunc { fn_: sha256 }.build(),
+            blake3: CtxFreeInputEvalFunc { fn_: blake3 }.build(),
+            value: CtxFreeInputEvalFunc { fn_: value }.build(),
+        }
+    }
+}
+
+impl CfgMod for HashLib {
+    fn extend(self, cfg: &mut Cfg) {
+        extend_func(cfg, SHA256, self.sha256);
+        extend_func(cfg, BLAKE3, self.blake3);
+        extend_func(cfg, VALUE, self.value);
+    }
+}
+
+// texts are hashed as utf-8 bytes
+pub fn sha256(cfg: &mut Cfg, input: Val) -> Val {
+    let digest = match &input {
+        Val::Byte(byte) => Sha256::digest(&***byte),
+        Val::Text(text) => Sha256::digest(text.as_bytes()),
+        _ => return bug!(cfg, "{SHA256}: expected input to be a byte or a text, but got {input}"),
+    };
+    Val::Byte(Byte::from(digest.to_vec()).into())
+}
+
+// texts are hashed as utf-8 bytes
+pub fn blake3(cfg: &mut Cfg, input: Val) -> Val {
+    let digest = match &input {
+        Val::Byte(byte) => blake3::hash(byte),
+        Val::Text(text) => blake3::hash(text.as_bytes()),
+        _ => return bug!(cfg, "{BLAKE3}: expected input to be a byte or a text, but got {input}"),
+    };
+    Val::Byte(Byte::from(digest.as_bytes().to_vec()).into())
+}
+
+// stable across runs and platforms, equal data have the same hash
+pub fn value(cfg: &mut Cfg, input: Val) -> Val {
+    let repr = match Repr::try_from(input) {
+        Ok(repr) => repr,
+        Err(val) => return bug!(cfg, "{VALUE}: expected data, but got {val}"),
+    };
+    let mut hasher = blake3::Hasher::new();
+    hash_repr(&mut hasher, &repr);
+    Val::Byte(Byte::from(hasher.finalize().as_bytes().to_vec()).into())
+}
+
+// a tag, then lengths as u64 in little endian before variable sized contents
+fn hash_repr(hasher: &mut blake3::Hasher, repr: &Repr) {
+    match repr {
+        Repr::Unit(_) => {
+            hasher.update(&[0]);
+        },
+        Repr::Bit(bit) => {
+            hasher.update(&[1, u8::from(**bit)]);
+        },
+        Repr::Key(key) => {
+            hasher.update(&[2]);
+            hash_bytes(hasher, key.as_bytes());
+        },
+        Repr::Text(text) => {
+            hasher.update(&[3]);
+            hash_bytes(hasher, text.as_bytes());
+        },
+        Repr::Int(int) => {
+            hasher.update(&[4]);
+            hash_bytes(hasher, &int.to_signed_bytes_le());
+        },
+        Repr::Decimal(decimal) => {
+            // equal decimals may have different scales
+            let (digits, scale) = decimal.normalized().into_bigint_and_scale();
+            hasher.update(&[5]);
+            hash_bytes(hasher, &digits.to_signed_bytes_le());
+            hasher.update(&scale.to_le_bytes());
+        },
+        Repr::Byte(byte) => {
+            hasher.update(&[6]);
+            hash_bytes(hasher, byte);
+        },
+        Repr::Cell(cell) => {
+            hasher.update(&[7]);
+            hash_repr(hasher, &cell.value);
+        },
+        Repr::Pair(pair) => {
+            hasher.update(&[8]);
+            hash_repr(hasher, &pair.left);
+            hash_repr(hasher, &pair.right);
+        },
+        Repr::Call(call) => {
+            hasher.update(&[9]);
+            hash_repr(hasher, &call.func);
+            hash_repr(hasher, &call.input);
+        },
+        Repr::List(list) => {
+            hasher.update(&[10]);
+            hasher.update(&(list.len() as u64).to_le_bytes());
+            for item in &**list {
+                hash_repr(hasher, item);
+            }
+        },
+        Repr::Map(map) => {
+            // maps are equal regardless of the order of entries
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(k1, _), (k2, _)| k1.as_bytes().cmp(k2.as_bytes()));
+            hasher.update(&[11]);
+            hasher.update(&(entries.len() as u64).to_le_bytes());
+            for (key, value) in entries {
+                hash_bytes(hasher, key.as_bytes());
+                hash_repr(hasher, value);
+            }
+        },
+    }
+}
+
+fn hash_bytes(hasher: &mut blake3::Hasher, bytes: &[u8]) {
+    hasher.update(&(bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
@@ -1,4 +1,7 @@
 use const_format::concatcp;
+use num_bigint::BigInt;
+use num_traits::Signed;
+use num_traits::ToPrimitive;
 use num_traits::Zero;
 
 use crate::bug;
@@ -12,6 +15,7 @@ use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
 use crate::type_::Int;
 use crate::type_::Pair;
+use crate::type_::Text;
 
 #[derive(Clone)]
 pub struct IntLib {
@@ -26,6 +30,22 @@ pub struct IntLib {
     pub greater: PrimFuncVal,
     pub greater_equal: PrimFuncVal,
     pub less_greater: PrimFuncVal,
+    pub power: PrimFuncVal,
+    pub modular_power: PrimFuncVal,
+    pub abs: PrimFuncVal,
+    pub negate: PrimFuncVal,
+    pub gcd: PrimFuncVal,
+    pub lcm: PrimFuncVal,
+    pub square_root: PrimFuncVal,
+    pub min: PrimFuncVal,
+    pub max: PrimFuncVal,
+    pub shift_left: PrimFuncVal,
+    pub shift_right: PrimFuncVal,
+    pub and: PrimFuncVal,
+    pub or: PrimFuncVal,
+    pub xor: PrimFuncVal,
+    pub to_text: PrimFuncVal,
+    pub from_text: PrimFuncVal,
 }
 
 pub const ADD: &str = concatcp!(PREFIX_ID, INT, ".add");
@@ -39,6 +59,25 @@ pub const LESS_EQUAL: &str = concatcp!(PREFIX_ID, INT, ".less_equal");
 pub const GREATER: &str = concatcp!(PREFIX_ID, INT, ".greater");
 pub const GREATER_EQUAL: &str = concatcp!(PREFIX_ID, INT, ".greater_equal");
 pub const LESS_GREATER: &str = concatcp!(PREFIX_ID, INT, ".less_greater");
+pub const POWER: &str = concatcp!(PREFIX_ID, INT, ".power");
+pub const MODULAR_POWER: &str = concatcp!(PREFIX_ID, INT, ".modular_power");
+pub const ABS: &str = concatcp!(PREFIX_ID, INT, ".abs");
+pub const NEGATE: &str = concatcp!(PREFIX_ID, INT, ".negate");
+pub const GCD: &str = concatcp!(PREFIX_ID, INT, ".gcd");
+pub const LCM: &str = concatcp!(PREFIX_ID, INT, ".lcm");
+pub const SQUARE_ROOT: &str = concatcp!(PREFIX_ID, INT, ".square_root");
+pub const MIN: &str = concatcp!(PREFIX_ID, INT, ".min");
+pub const MAX: &str = concatcp!(PREFIX_ID, INT, ".max");
+pub const SHIFT_LEFT: &str = concatcp!(PREFIX_ID, INT, ".shift_left");
+pub const SHIFT_RIGHT: &str = concatcp!(PREFIX_ID, INT, ".shift_right");
+pub const AND: &str = concatcp!(PREFIX_ID, INT, ".and");
+pub const OR: &str = concatcp!(PREFIX_ID, INT, ".or");
+pub const XOR: &str = concatcp!(PREFIX_ID, INT, ".xor");
+pub const TO_TEXT: &str = concatcp!(PREFIX_ID, INT, ".to_text");
+pub const FROM_TEXT: &str = concatcp!(PREFIX_ID, INT, ".from_text");
+
+// results of power and shift_left larger than this are rejected instead of allocated
+pub(crate) const MAX_BITS: u64 = 1 << 24;
 
 impl Default for IntLib {
     fn default() -> Self {
@@ -54,6 +93,22 @@ impl Default for IntLib {
             greater: CtxFreeInputEvalFunc { fn_: greater }.build(),
             greater_equal: CtxFreeInputEvalFunc { fn_: greater_equal }.build(),
             less_greater: CtxFreeInputEvalFunc { fn_: less_greater }.build(),
+            power: CtxFreeInputEvalFunc { fn_: power }.build(),
+            modular_power: CtxFreeInputEvalFunc { fn_: modular_power }.build(),
+            abs: CtxFreeInputEvalFunc { fn_: abs }.build(),
+            negate: CtxFreeInputEvalFunc { fn_: negate }.build(),
+            gcd: CtxFreeInputEvalFunc { fn_: gcd }.build(),
+            lcm: CtxFreeInputEvalFunc { fn_: lcm }.build(),
+            square_root: CtxFreeInputEvalFunc { fn_: square_root }.build(),
+            min: CtxFreeInputEvalFunc { fn_: min }.build(),
+            max: CtxFreeInputEvalFunc { fn_: max }.build(),
+            shift_left: CtxFreeInputEvalFunc { fn_: shift_left }.build(),
+            shift_right: CtxFreeInputEvalFunc { fn_: shift_right }.build(),
+            and: CtxFreeInputEvalFunc { fn_: and }.build(),
+            or: CtxFreeInputEvalFunc { fn_: or }.build(),
+            xor: CtxFreeInputEvalFunc { fn_: xor }.build(),
+            to_text: CtxFreeInputEvalFunc { fn_: to_text }.build(),
+            from_text: CtxFreeInputEvalFunc { fn_: from_text }.build(),
         }
     }
 }
@@ -71,6 +126,22 @@ impl CfgMod for IntLib {
         extend_func(cfg, GREATER, self.greater);
         extend_func(cfg, GREATER_EQUAL, self.greater_equal);
         extend_func(cfg, LESS_GREATER, self.less_greater);
+        extend_func(cfg, POWER, self.power);
+        extend_func(cfg, MODULAR_POWER, self.modular_power);
+        extend_func(cfg, ABS, self.abs);
+        extend_func(cfg, NEGATE, self.negate);
+        extend_func(cfg, GCD, self.gcd);
+        extend_func(cfg, LCM, self.lcm);
+        extend_func(cfg, SQUARE_ROOT, self.square_root);
+        extend_func(cfg, MIN, self.min);
+        extend_func(cfg, MAX, self.max);
+        extend_func(cfg, SHIFT_LEFT, self.shift_left);
+        extend_func(cfg, SHIFT_RIGHT, self.shift_right);
+        extend_func(cfg, AND, self.and);
+        extend_func(cfg, OR, self.or);
+        extend_func(cfg, XOR, self.xor);
+        extend_func(cfg, TO_TEXT, self.to_text);
+        extend_func(cfg, FROM_TEXT, self.from_text);
     }
 }
 
@@ -163,6 +234,196 @@ pub fn less_greater(cfg: &mut Cfg, input: Val) -> Val {
     Val::Bit(i1.less_greater(&i2))
 }
 
+pub fn power(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((base, exponent)) = int_pair(cfg, POWER, input) else {
+        return Val::default();
+    };
+    let Some(exponent) = exponent.to_u32() else {
+        return bug!(cfg, "{POWER}: expected input.right to be in 0 : {}", u32::MAX);
+    };
+    // the result has at most base.bits * exponent bits
+    if base.bits() > 1 && base.bits().saturating_mul(exponent.into()) > MAX_BITS {
+        return bug!(cfg, "{POWER}: expected the result to have at most {MAX_BITS} bits");
+    }
+    Val::Int(base.power(exponent).into())
+}
+
+// base : exponent : modulus
+pub fn modular_power(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((base, rest)) = int_pair_right(cfg, MODULAR_POWER, input) else {
+        return Val::default();
+    };
+    let Some((exponent, modulus)) = int_pair(cfg, MODULAR_POWER, rest) else {
+        return Val::default();
+    };
+    if exponent.is_negative() {
+        return bug!(cfg, "{MODULAR_POWER}: expected exponent to be non-negative");
+    }
+    if modulus.is_zero() {
+        return bug!(cfg, "{MODULAR_POWER}: expected modulus to be non-zero");
+    }
+    Val::Int(base.modular_power(&exponent, &modulus).into())
+}
+
+pub fn abs(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Int(i) = input else {
+        return bug!(cfg, "{ABS}: expected input to be an integer, but got {input}");
+    };
+    Val::Int(i.abs().into())
+}
+
+pub fn negate(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Int(i) = input else {
+        return bug!(cfg, "{NEGATE}: expected input to be an integer, but got {input}");
+    };
+    Val::Int((-Int::from(i)).into())
+}
+
+pub fn gcd(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, GCD, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.gcd(&i2).into())
+}
+
+pub fn lcm(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, LCM, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.lcm(&i2).into())
+}
+
+pub fn square_root(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Int(i) = input else {
+        return bug!(cfg, "{SQUARE_ROOT}: expected input to be an integer, but got {input}");
+    };
+    if i.is_negative() {
+        return bug!(cfg, "{SQUARE_ROOT}: expected input to be non-negative, but got {i}");
+    }
+    Val::Int(i.square_root().into())
+}
+
+pub fn min(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, MIN, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.min(i2).into())
+}
+
+pub fn max(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, MAX, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.max(i2).into())
+}
+
+pub fn shift_left(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i, n)) = int_pair(cfg, SHIFT_LEFT, input) else {
+        return Val::default();
+    };
+    let Some(n) = n.to_u64() else {
+        return bug!(cfg, "{SHIFT_LEFT}: expected input.right to be non-negative");
+    };
+    if i.is_zero() {
+        return Val::Int(i.into());
+    }
+    if i.bits().saturating_add(n) > MAX_BITS {
+        return bug!(cfg, "{SHIFT_LEFT}: expected the result to have at most {MAX_BITS} bits");
+    }
+    Val::Int(i.shift_left(n as usize).into())
+}
+
+pub fn shift_right(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i, n)) = int_pair(cfg, SHIFT_RIGHT, input) else {
+        return Val::default();
+    };
+    let Some(n) = n.to_usize() else {
+        return bug!(cfg, "{SHIFT_RIGHT}: expected input.right to be non-negative");
+    };
+    Val::Int(i.shift_right(n).into())
+}
+
+pub fn and(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, AND, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.and(i2).into())
+}
+
+pub fn or(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, OR, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.or(i2).into())
+}
+
+pub fn xor(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i1, i2)) = int_pair(cfg, XOR, input) else {
+        return Val::default();
+    };
+    Val::Int(i1.xor(i2).into())
+}
+
+// integer : radix
+pub fn to_text(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((i, radix)) = int_pair(cfg, TO_TEXT, input) else {
+        return Val::default();
+    };
+    let Some(radix) = to_radix(cfg, TO_TEXT, &radix) else {
+        return Val::default();
+    };
+    Val::Text(Text::from(i.to_str_radix(radix)).into())
+}
+
+// text : radix
+pub fn from_text(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{FROM_TEXT}: expected input to be a pair, but got {input}");
+    };
+    let Val::Text(text) = &pair.left else {
+        return bug!(cfg, "{FROM_TEXT}: expected input.left to be a text, but got {}", pair.left);
+    };
+    let Val::Int(radix) = &pair.right else {
+        return bug!(
+            cfg,
+            "{FROM_TEXT}: expected input.right to be an integer, but got {}",
+            pair.right
+        );
+    };
+    let Some(radix) = to_radix(cfg, FROM_TEXT, radix) else {
+        return Val::default();
+    };
+    // parse_bytes accepts underscores between digits
+    if text.contains('_') {
+        return Val::default();
+    }
+    let Some(i) = BigInt::parse_bytes(text.as_bytes(), radix) else {
+        return Val::default();
+    };
+    Val::Int(Int::from(i).into())
+}
+
+fn to_radix(cfg: &mut Cfg, tag: &str, radix: &Int) -> Option<u32> {
+    let radix = radix.to_u32().filter(|radix| (2 ..= 36).contains(radix));
+    if radix.is_none() {
+        bug!(cfg, "{tag}: expected radix to be in 2 : 36");
+    }
+    radix
+}
+
+fn int_pair_right(cfg: &mut Cfg, tag: &str, input: Val) -> Option<(Int, Val)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::Int(i) = pair.left else {
+        bug!(cfg, "{tag}: expected input.left to be an integer, but got {}", pair.left);
+        return None;
+    };
+    Some((i.into(), pair.right))
+}
+
 fn int_pair(cfg: &mut Cfg, tag: &str, input: Val) -> Option<(Int, Int)> {
     let Val::Pair(pair) = input else {
         bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
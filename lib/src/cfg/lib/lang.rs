@@ -15,6 +15,9 @@ use crate::semantics::func::PrimFunc;
 use crate::semantics::func::PrimInput;
 use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
+use crate::syntax;
+use crate::syntax::repr::Repr;
+use crate::type_::Byte;
 use crate::type_::Cell;
 use crate::type_::Text;
 
@@ -26,6 +29,10 @@ pub struct LangLib {
     pub eval: PrimFuncVal,
     pub parse: PrimFuncVal,
     pub generate: PrimFuncVal,
+    pub parse_json: PrimFuncVal,
+    pub generate_json: PrimFuncVal,
+    pub encode: PrimFuncVal,
+    pub decode: PrimFuncVal,
 }
 
 const LANGUAGE: &str = "language";
@@ -36,6 +43,10 @@ pub const CODE: &str = concatcp!(PREFIX_ID, LANGUAGE, ".semantics.code");
 pub const EVAL: &str = concatcp!(PREFIX_ID, LANGUAGE, ".semantics.eval");
 pub const PARSE: &str = concatcp!(PREFIX_ID, LANGUAGE, ".syntax.parse");
 pub const GENERATE: &str = concatcp!(PREFIX_ID, LANGUAGE, ".syntax.generate");
+pub const PARSE_JSON: &str = concatcp!(PREFIX_ID, LANGUAGE, ".json.parse");
+pub const GENERATE_JSON: &str = concatcp!(PREFIX_ID, LANGUAGE, ".json.generate");
+pub const ENCODE: &str = concatcp!(PREFIX_ID, LANGUAGE, ".binary.encode");
+pub const DECODE: &str = concatcp!(PREFIX_ID, LANGUAGE, ".binary.decode");
 
 impl Default for LangLib {
     fn default() -> Self {
@@ -46,6 +57,10 @@ impl Default for LangLib {
             eval: PrimFunc { fn_: Rc::new(Eval), ctx: PrimCtx::Mut, input: PrimInput::Eval }.into(),
             parse: CtxFreeInputEvalFunc { fn_: parse }.build(),
             generate: CtxFreeInputEvalFunc { fn_: generate }.build(),
+            parse_json: CtxFreeInputEvalFunc { fn_: parse_json }.build(),
+            generate_json: CtxFreeInputEvalFunc { fn_: generate_json }.build(),
+            encode: CtxFreeInputEvalFunc { fn_: encode }.build(),
+            decode: CtxFreeInputEvalFunc { fn_: decode }.build(),
         }
     }
 }
@@ -58,6 +73,10 @@ impl CfgMod for LangLib {
         extend_func(cfg, EVAL, self.eval);
         extend_func(cfg, PARSE, self.parse);
         extend_func(cfg, GENERATE, self.generate);
+        extend_func(cfg, PARSE_JSON, self.parse_json);
+        extend_func(cfg, GENERATE_JSON, self.generate_json);
+        extend_func(cfg, ENCODE, self.encode);
+        extend_func(cfg, DECODE, self.decode);
     }
 }
 
@@ -75,3 +94,41 @@ pub fn generate(_cfg: &mut Cfg, input: Val) -> Val {
     let str = format!("{input:#}");
     Val::Text(Text::from(str).into())
 }
+
+pub fn parse_json(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Text(input) = input else {
+        return bug!(cfg, "{PARSE_JSON}: expected input to be a text, but got {input}");
+    };
+    let Ok(val) = syntax::parse_json(&input) else {
+        return Val::default();
+    };
+    Val::Cell(Cell::new(val).into())
+}
+
+pub fn generate_json(cfg: &mut Cfg, input: Val) -> Val {
+    let repr = match Repr::try_from(input) {
+        Ok(repr) => repr,
+        Err(val) => return bug!(cfg, "{GENERATE_JSON}: expected data, but got {val}"),
+    };
+    let str = syntax::generate_json(&repr);
+    Val::Text(Text::from(str).into())
+}
+
+pub fn encode(cfg: &mut Cfg, input: Val) -> Val {
+    let repr = match Repr::try_from(input) {
+        Ok(repr) => repr,
+        Err(val) => return bug!(cfg, "{ENCODE}: expected data, but got {val}"),
+    };
+    let bytes = syntax::encode_binary(&repr);
+    Val::Byte(Byte::from(bytes).into())
+}
+
+pub fn decode(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Byte(input) = input else {
+        return bug!(cfg, "{DECODE}: expected input to be a byte, but got {input}");
+    };
+    let Ok(val) = syntax::decode_binary(&input) else {
+        return Val::default();
+    };
+    Val::Cell(Cell::new(val).into())
+}
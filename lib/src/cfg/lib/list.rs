@@ -1,17 +1,23 @@
+use std::cmp::Ordering;
 use std::mem::swap;
 
 use const_format::concatcp;
+use num_bigint::Sign;
 use num_traits::ToPrimitive;
 
 use crate::bug;
 use crate::cfg::CfgMod;
 use crate::cfg::extend_func;
+use crate::cfg::lib::value::compare_val;
 use crate::semantics::cfg::Cfg;
 use crate::semantics::core::PREFIX_ID;
 use crate::semantics::func::CtxConstInputEvalFunc;
 use crate::semantics::func::CtxConstInputFreeFunc;
+use crate::semantics::func::CtxFreeInputEvalFunc;
 use crate::semantics::func::CtxMutInputEvalFunc;
 use crate::semantics::func::CtxMutInputFreeFunc;
+use crate::semantics::func::DynFunc;
+use crate::semantics::val::FuncVal;
 use crate::semantics::val::LIST;
 use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
@@ -36,6 +42,17 @@ pub struct ListLib {
     pub pop: PrimFuncVal,
     pub pop_many: PrimFuncVal,
     pub clear: PrimFuncVal,
+    pub contain: PrimFuncVal,
+    pub reverse: PrimFuncVal,
+    pub zip: PrimFuncVal,
+    pub flatten: PrimFuncVal,
+    pub map: PrimFuncVal,
+    pub filter: PrimFuncVal,
+    pub fold: PrimFuncVal,
+    pub any: PrimFuncVal,
+    pub all: PrimFuncVal,
+    pub find: PrimFuncVal,
+    pub sort: PrimFuncVal,
 }
 
 pub const GET_LENGTH: &str = concatcp!(PREFIX_ID, LIST, ".get_length");
@@ -52,6 +69,17 @@ pub const PUSH_MANY: &str = concatcp!(PREFIX_ID, LIST, ".push_many");
 pub const POP: &str = concatcp!(PREFIX_ID, LIST, ".pop");
 pub const POP_MANY: &str = concatcp!(PREFIX_ID, LIST, ".pop_many");
 pub const CLEAR: &str = concatcp!(PREFIX_ID, LIST, ".clear");
+pub const CONTAIN: &str = concatcp!(PREFIX_ID, LIST, ".contain");
+pub const REVERSE: &str = concatcp!(PREFIX_ID, LIST, ".reverse");
+pub const ZIP: &str = concatcp!(PREFIX_ID, LIST, ".zip");
+pub const FLATTEN: &str = concatcp!(PREFIX_ID, LIST, ".flatten");
+pub const MAP: &str = concatcp!(PREFIX_ID, LIST, ".map");
+pub const FILTER: &str = concatcp!(PREFIX_ID, LIST, ".filter");
+pub const FOLD: &str = concatcp!(PREFIX_ID, LIST, ".fold");
+pub const ANY: &str = concatcp!(PREFIX_ID, LIST, ".any");
+pub const ALL: &str = concatcp!(PREFIX_ID, LIST, ".all");
+pub const FIND: &str = concatcp!(PREFIX_ID, LIST, ".find");
+pub const SORT: &str = concatcp!(PREFIX_ID, LIST, ".sort");
 
 impl Default for ListLib {
     fn default() -> Self {
@@ -70,6 +98,17 @@ impl Default for ListLib {
             pop: CtxMutInputFreeFunc { fn_: pop }.build(),
             pop_many: CtxMutInputEvalFunc { fn_: pop_many }.build(),
             clear: CtxMutInputFreeFunc { fn_: clear }.build(),
+            contain: CtxConstInputEvalFunc { fn_: contain }.build(),
+            reverse: CtxMutInputFreeFunc { fn_: reverse }.build(),
+            zip: CtxFreeInputEvalFunc { fn_: zip }.build(),
+            flatten: CtxFreeInputEvalFunc { fn_: flatten }.build(),
+            map: CtxMutInputEvalFunc { fn_: map }.build(),
+            filter: CtxMutInputEvalFunc { fn_: filter }.build(),
+            fold: CtxMutInputEvalFunc { fn_: fold }.build(),
+            any: CtxMutInputEvalFunc { fn_: any }.build(),
+            all: CtxMutInputEvalFunc { fn_: all }.build(),
+            find: CtxMutInputEvalFunc { fn_: find }.build(),
+            sort: CtxMutInputEvalFunc { fn_: sort }.build(),
         }
     }
 }
@@ -90,6 +129,17 @@ impl CfgMod for ListLib {
         extend_func(cfg, POP, self.pop);
         extend_func(cfg, POP_MANY, self.pop_many);
         extend_func(cfg, CLEAR, self.clear);
+        extend_func(cfg, CONTAIN, self.contain);
+        extend_func(cfg, REVERSE, self.reverse);
+        extend_func(cfg, ZIP, self.zip);
+        extend_func(cfg, FLATTEN, self.flatten);
+        extend_func(cfg, MAP, self.map);
+        extend_func(cfg, FILTER, self.filter);
+        extend_func(cfg, FOLD, self.fold);
+        extend_func(cfg, ANY, self.any);
+        extend_func(cfg, ALL, self.all);
+        extend_func(cfg, FIND, self.find);
+        extend_func(cfg, SORT, self.sort);
     }
 }
 
@@ -312,7 +362,252 @@ pub fn clear(cfg: &mut Cfg, ctx: &mut Val) -> Val {
     Val::default()
 }
 
-fn to_index(cfg: &mut Cfg, key: &str, val: Val) -> Option<usize> {
+pub fn contain(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::List(list) = ctx else {
+        return bug!(cfg, "{CONTAIN}: expected context to be a list, but got {ctx}");
+    };
+    Val::Bit(list.contains(&input).into())
+}
+
+pub fn reverse(cfg: &mut Cfg, ctx: &mut Val) -> Val {
+    let Val::List(list) = ctx else {
+        return bug!(cfg, "{REVERSE}: expected context to be a list, but got {ctx}");
+    };
+    list.reverse();
+    Val::default()
+}
+
+// stops at the end of the shorter list
+pub fn zip(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{ZIP}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::List(left) = pair.left else {
+        return bug!(cfg, "{ZIP}: expected input.left to be a list, but got {}", pair.left);
+    };
+    let Val::List(right) = pair.right else {
+        return bug!(cfg, "{ZIP}: expected input.right to be a list, but got {}", pair.right);
+    };
+    let left = List::from(left);
+    let right = List::from(right);
+    let list: List<Val> = left
+        .into_iter()
+        .zip(right)
+        .map(|(left, right)| Val::Pair(Pair::new(left, right).into()))
+        .collect();
+    Val::List(list.into())
+}
+
+// only flattens one level
+pub fn flatten(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::List(lists) = input else {
+        return bug!(cfg, "{FLATTEN}: expected input to be a list, but got {input}");
+    };
+    let mut flat = Vec::new();
+    for list in List::from(lists) {
+        let Val::List(list) = list else {
+            return bug!(cfg, "{FLATTEN}: expected item to be a list, but got {list}");
+        };
+        flat.extend(List::from(list));
+    }
+    Val::List(List::from(flat).into())
+}
+
+// functions are called in the current context
+pub fn map(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, MAP, input) else {
+        return Val::default();
+    };
+    let mut mapped = Vec::with_capacity(list.len());
+    for item in list {
+        if cfg.is_aborted() {
+            return Val::default();
+        }
+        mapped.push(func.call(cfg, ctx, item));
+    }
+    Val::List(List::from(mapped).into())
+}
+
+pub fn filter(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, FILTER, input) else {
+        return Val::default();
+    };
+    let mut filtered = Vec::new();
+    for item in list {
+        let Some(keep) = predicate(cfg, FILTER, ctx, &func, item.clone()) else {
+            return Val::default();
+        };
+        if keep {
+            filtered.push(item);
+        }
+    }
+    Val::List(List::from(filtered).into())
+}
+
+// the function is called with accumulator : item
+pub fn fold(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{FOLD}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::List(list) = pair.left else {
+        return bug!(cfg, "{FOLD}: expected input.left to be a list, but got {}", pair.left);
+    };
+    let Val::Pair(init_func) = pair.right else {
+        return bug!(cfg, "{FOLD}: expected input.right to be a pair, but got {}", pair.right);
+    };
+    let init_func = Pair::from(init_func);
+    let Val::Func(func) = init_func.right else {
+        return bug!(cfg, "{FOLD}: expected input.right.right to be a function, \
+            but got {}", init_func.right);
+    };
+    let mut acc = init_func.left;
+    for item in List::from(list) {
+        if cfg.is_aborted() {
+            return Val::default();
+        }
+        acc = func.call(cfg, ctx, Val::Pair(Pair::new(acc, item).into()));
+    }
+    acc
+}
+
+pub fn any(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, ANY, input) else {
+        return Val::default();
+    };
+    for item in list {
+        let Some(b) = predicate(cfg, ANY, ctx, &func, item) else {
+            return Val::default();
+        };
+        if b {
+            return Val::Bit(true.into());
+        }
+    }
+    Val::Bit(false.into())
+}
+
+pub fn all(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, ALL, input) else {
+        return Val::default();
+    };
+    for item in list {
+        let Some(b) = predicate(cfg, ALL, ctx, &func, item) else {
+            return Val::default();
+        };
+        if !b {
+            return Val::Bit(false.into());
+        }
+    }
+    Val::Bit(true.into())
+}
+
+// returns the first matched item, or unit if not found
+pub fn find(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, FIND, input) else {
+        return Val::default();
+    };
+    for item in list {
+        let Some(b) = predicate(cfg, FIND, ctx, &func, item.clone()) else {
+            return Val::default();
+        };
+        if b {
+            return item;
+        }
+    }
+    Val::default()
+}
+
+// a stable sort, the function is called with left : right
+// and returns a negative, zero or positive integer
+// a list without a function is sorted by the total order of data
+pub fn sort(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    if let Val::List(list) = input {
+        let mut compare = compare_val;
+        let Some(sorted) = merge_sort(List::from(list).into(), &mut compare) else {
+            return bug!(cfg, "{SORT}: expected items to be data");
+        };
+        return Val::List(List::from(sorted).into());
+    }
+    let Some((list, func)) = list_func(cfg, SORT, input) else {
+        return Val::default();
+    };
+    let mut compare = |left: &Val, right: &Val| {
+        if cfg.is_aborted() {
+            return None;
+        }
+        let input = Val::Pair(Pair::new(left.clone(), right.clone()).into());
+        let output = func.call(cfg, ctx, input);
+        let Val::Int(i) = output else {
+            bug!(cfg, "{SORT}: expected function output to be an integer, but got {output}");
+            return None;
+        };
+        let ordering = match i.sign() {
+            Sign::Minus => Ordering::Less,
+            Sign::NoSign => Ordering::Equal,
+            Sign::Plus => Ordering::Greater,
+        };
+        Some(ordering)
+    };
+    let Some(sorted) = merge_sort(list.into(), &mut compare) else {
+        return Val::default();
+    };
+    Val::List(List::from(sorted).into())
+}
+
+pub(crate) fn list_func(cfg: &mut Cfg, key: &str, input: Val) -> Option<(List<Val>, FuncVal)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{key}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::List(list) = pair.left else {
+        bug!(cfg, "{key}: expected input.left to be a list, but got {}", pair.left);
+        return None;
+    };
+    let Val::Func(func) = pair.right else {
+        bug!(cfg, "{key}: expected input.right to be a function, but got {}", pair.right);
+        return None;
+    };
+    Some((List::from(list), func))
+}
+
+pub(crate) fn predicate(
+    cfg: &mut Cfg, key: &str, ctx: &mut Val, func: &FuncVal, item: Val,
+) -> Option<bool> {
+    if cfg.is_aborted() {
+        return None;
+    }
+    let output = func.call(cfg, ctx, item);
+    let Val::Bit(b) = output else {
+        bug!(cfg, "{key}: expected function output to be a bit, but got {output}");
+        return None;
+    };
+    Some(*b)
+}
+
+// user functions may not be a total order, so we don't rely on the std sort
+fn merge_sort<F>(mut items: Vec<Val>, compare: &mut F) -> Option<Vec<Val>>
+where F: FnMut(&Val, &Val) -> Option<Ordering> {
+    if items.len() <= 1 {
+        return Some(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, compare)?;
+    let right = merge_sort(right, compare)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let item = if compare(l, r)?.is_gt() { right.next() } else { left.next() };
+        merged.extend(item);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Some(merged)
+}
+
+pub(crate) fn to_index(cfg: &mut Cfg, key: &str, val: Val) -> Option<usize> {
     let Val::Int(i) = val else {
         bug!(cfg, "{key}: expected index to be an integer, but got {val}");
         return None;
@@ -320,7 +615,7 @@ fn to_index(cfg: &mut Cfg, key: &str, val: Val) -> Option<usize> {
     i.to_usize()
 }
 
-fn to_range(
+pub(crate) fn to_range(
     cfg: &mut Cfg, key: &str, pair: Pair<Val, Val>,
 ) -> Option<(Option<usize>, Option<usize>)> {
     let from = match pair.left {
@@ -1,10 +1,13 @@
 use std::mem::swap;
+use std::mem::take;
 
 use const_format::concatcp;
 
 use crate::bug;
 use crate::cfg::CfgMod;
 use crate::cfg::extend_func;
+use crate::cfg::lib::list::list_func;
+use crate::cfg::lib::list::predicate;
 use crate::semantics::cfg::Cfg;
 use crate::semantics::core::PREFIX_ID;
 use crate::semantics::func::CtxConstInputEvalFunc;
@@ -12,6 +15,8 @@ use crate::semantics::func::CtxConstInputFreeFunc;
 use crate::semantics::func::CtxFreeInputEvalFunc;
 use crate::semantics::func::CtxMutInputEvalFunc;
 use crate::semantics::func::CtxMutInputFreeFunc;
+use crate::semantics::func::DynFunc;
+use crate::semantics::val::FuncVal;
 use crate::semantics::val::MAP;
 use crate::semantics::val::PrimFuncVal;
 use crate::semantics::val::Val;
@@ -46,6 +51,18 @@ pub struct MapLib {
     pub remove_many: PrimFuncVal,
     pub move_: PrimFuncVal,
     pub clear: PrimFuncVal,
+    pub map_values: PrimFuncVal,
+    pub filter: PrimFuncVal,
+    pub merge: PrimFuncVal,
+    pub deep_merge: PrimFuncVal,
+    pub invert: PrimFuncVal,
+    pub group_by: PrimFuncVal,
+    pub union: PrimFuncVal,
+    pub intersection: PrimFuncVal,
+    pub difference: PrimFuncVal,
+    pub symmetric_difference: PrimFuncVal,
+    pub is_subset: PrimFuncVal,
+    pub get_sorted_keys: PrimFuncVal,
 }
 
 pub const MAKE: &str = concatcp!(PREFIX_ID, MAP, ".make");
@@ -68,6 +85,18 @@ pub const REMOVE: &str = concatcp!(PREFIX_ID, MAP, ".remove");
 pub const REMOVE_MANY: &str = concatcp!(PREFIX_ID, MAP, ".remove_many");
 pub const MOVE: &str = concatcp!(PREFIX_ID, MAP, ".move");
 pub const CLEAR: &str = concatcp!(PREFIX_ID, MAP, ".clear");
+pub const MAP_VALUES: &str = concatcp!(PREFIX_ID, MAP, ".map_values");
+pub const FILTER: &str = concatcp!(PREFIX_ID, MAP, ".filter");
+pub const MERGE: &str = concatcp!(PREFIX_ID, MAP, ".merge");
+pub const DEEP_MERGE: &str = concatcp!(PREFIX_ID, MAP, ".deep_merge");
+pub const INVERT: &str = concatcp!(PREFIX_ID, MAP, ".invert");
+pub const GROUP_BY: &str = concatcp!(PREFIX_ID, MAP, ".group_by");
+pub const UNION: &str = concatcp!(PREFIX_ID, MAP, ".union");
+pub const INTERSECTION: &str = concatcp!(PREFIX_ID, MAP, ".intersection");
+pub const DIFFERENCE: &str = concatcp!(PREFIX_ID, MAP, ".difference");
+pub const SYMMETRIC_DIFFERENCE: &str = concatcp!(PREFIX_ID, MAP, ".symmetric_difference");
+pub const IS_SUBSET: &str = concatcp!(PREFIX_ID, MAP, ".is_subset");
+pub const GET_SORTED_KEYS: &str = concatcp!(PREFIX_ID, MAP, ".get_sorted_keys");
 
 impl Default for MapLib {
     fn default() -> Self {
@@ -92,6 +121,18 @@ impl Default for MapLib {
             remove_many: CtxMutInputEvalFunc { fn_: remove_many }.build(),
             move_: CtxMutInputEvalFunc { fn_: move_ }.build(),
             clear: CtxMutInputFreeFunc { fn_: clear }.build(),
+            map_values: CtxMutInputEvalFunc { fn_: map_values }.build(),
+            filter: CtxMutInputEvalFunc { fn_: filter }.build(),
+            merge: CtxMutInputEvalFunc { fn_: merge }.build(),
+            deep_merge: CtxFreeInputEvalFunc { fn_: deep_merge }.build(),
+            invert: CtxFreeInputEvalFunc { fn_: invert }.build(),
+            group_by: CtxMutInputEvalFunc { fn_: group_by }.build(),
+            union: CtxFreeInputEvalFunc { fn_: union }.build(),
+            intersection: CtxFreeInputEvalFunc { fn_: intersection }.build(),
+            difference: CtxFreeInputEvalFunc { fn_: difference }.build(),
+            symmetric_difference: CtxFreeInputEvalFunc { fn_: symmetric_difference }.build(),
+            is_subset: CtxFreeInputEvalFunc { fn_: is_subset }.build(),
+            get_sorted_keys: CtxConstInputFreeFunc { fn_: get_sorted_keys }.build(),
         }
     }
 }
@@ -118,6 +159,18 @@ impl CfgMod for MapLib {
         extend_func(cfg, REMOVE_MANY, self.remove_many);
         extend_func(cfg, MOVE, self.move_);
         extend_func(cfg, CLEAR, self.clear);
+        extend_func(cfg, MAP_VALUES, self.map_values);
+        extend_func(cfg, FILTER, self.filter);
+        extend_func(cfg, MERGE, self.merge);
+        extend_func(cfg, DEEP_MERGE, self.deep_merge);
+        extend_func(cfg, INVERT, self.invert);
+        extend_func(cfg, GROUP_BY, self.group_by);
+        extend_func(cfg, UNION, self.union);
+        extend_func(cfg, INTERSECTION, self.intersection);
+        extend_func(cfg, DIFFERENCE, self.difference);
+        extend_func(cfg, SYMMETRIC_DIFFERENCE, self.symmetric_difference);
+        extend_func(cfg, IS_SUBSET, self.is_subset);
+        extend_func(cfg, GET_SORTED_KEYS, self.get_sorted_keys);
     }
 }
 
@@ -341,7 +394,7 @@ pub fn remove(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
     let Val::Key(key) = input else {
         return bug!(cfg, "{REMOVE}: expected input to be a key, but got {input}");
     };
-    let Some(value) = map.remove(&key) else {
+    let Some(value) = map.shift_remove(&key) else {
         return Val::default();
     };
     Val::Cell(Cell::new(value).into())
@@ -360,7 +413,7 @@ pub fn remove_many(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
         let Val::Key(key) = key else {
             return bug!(cfg, "{REMOVE_MANY}: expected input.item to be a key, but got {key}");
         };
-        if let Some(val) = map.remove(&key) {
+        if let Some(val) = map.shift_remove(&key) {
             new_map.insert(key, val);
         }
     }
@@ -374,7 +427,7 @@ pub fn move_(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
     let Val::Key(key) = input else {
         return bug!(cfg, "{MOVE}: expected input to be a key, but got {input}");
     };
-    let Some(value) = map.remove(&key) else {
+    let Some(value) = map.shift_remove(&key) else {
         return bug!(cfg, "{MOVE}: value not found for key {key} in the map {map}");
     };
     value
@@ -387,3 +440,230 @@ pub fn clear(cfg: &mut Cfg, ctx: &mut Val) -> Val {
     map.clear();
     Val::default()
 }
+
+// functions are called in the current context
+pub fn map_values(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((map, func)) = map_func(cfg, MAP_VALUES, input) else {
+        return Val::default();
+    };
+    let mut new_map: Map<Key, Val> = Map::with_capacity(map.len());
+    for (key, value) in map {
+        if cfg.is_aborted() {
+            return Val::default();
+        }
+        new_map.insert(key, func.call(cfg, ctx, value));
+    }
+    Val::Map(new_map.into())
+}
+
+// the predicate is called with key : value
+pub fn filter(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((map, func)) = map_func(cfg, FILTER, input) else {
+        return Val::default();
+    };
+    let mut new_map: Map<Key, Val> = Map::default();
+    for (key, value) in map {
+        let item = Val::Pair(Pair::new(Val::Key(key.clone()), value.clone()).into());
+        let Some(keep) = predicate(cfg, FILTER, ctx, &func, item) else {
+            return Val::default();
+        };
+        if keep {
+            new_map.insert(key, value);
+        }
+    }
+    Val::Map(new_map.into())
+}
+
+// the resolver is called with left_value : right_value when both maps have the key
+pub fn merge(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{MERGE}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Map(left) = pair.left else {
+        return bug!(cfg, "{MERGE}: expected input.left to be a map, but got {}", pair.left);
+    };
+    let Val::Pair(right_func) = pair.right else {
+        return bug!(cfg, "{MERGE}: expected input.right to be a pair, but got {}", pair.right);
+    };
+    let right_func = Pair::from(right_func);
+    let Val::Map(right) = right_func.left else {
+        return bug!(cfg, "{MERGE}: expected input.right.left to be a map, \
+            but got {}", right_func.left);
+    };
+    let Val::Func(func) = right_func.right else {
+        return bug!(cfg, "{MERGE}: expected input.right.right to be a function, \
+            but got {}", right_func.right);
+    };
+    let mut merged = Map::from(left);
+    for (key, value) in Map::from(right) {
+        if cfg.is_aborted() {
+            return Val::default();
+        }
+        let Some(current) = merged.get_mut(&key) else {
+            merged.insert(key, value);
+            continue;
+        };
+        let left = take(current);
+        *current = func.call(cfg, ctx, Val::Pair(Pair::new(left, value).into()));
+    }
+    Val::Map(merged.into())
+}
+
+// nested maps are merged recursively, other values on the right side replace the left ones
+pub fn deep_merge(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{DEEP_MERGE}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Map(left) = pair.left else {
+        return bug!(cfg, "{DEEP_MERGE}: expected input.left to be a map, but got {}", pair.left);
+    };
+    let Val::Map(right) = pair.right else {
+        return bug!(cfg, "{DEEP_MERGE}: expected input.right to be a map, \
+            but got {}", pair.right);
+    };
+    Val::Map(merge_map(left.into(), right.into()).into())
+}
+
+fn merge_map(mut left: Map<Key, Val>, right: Map<Key, Val>) -> Map<Key, Val> {
+    for (key, value) in right {
+        let value = match (left.get_mut(&key), value) {
+            (Some(Val::Map(left_map)), Val::Map(right_map)) => {
+                let left_map = take(&mut **left_map);
+                Val::Map(merge_map(left_map, right_map.into()).into())
+            },
+            (_, value) => value,
+        };
+        left.insert(key, value);
+    }
+    left
+}
+
+// values must be keys, a later entry wins when values collide
+pub fn invert(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Map(map) = input else {
+        return bug!(cfg, "{INVERT}: expected input to be a map, but got {input}");
+    };
+    let map = Map::from(map);
+    let mut inverted: Map<Key, Val> = Map::with_capacity(map.len());
+    for (key, value) in map {
+        let Val::Key(value) = value else {
+            return bug!(cfg, "{INVERT}: expected value to be a key, but got {value}");
+        };
+        inverted.insert(value, Val::Key(key));
+    }
+    Val::Map(inverted.into())
+}
+
+// the function maps each item of the list to a key, items keep their order in each group
+pub fn group_by(cfg: &mut Cfg, ctx: &mut Val, input: Val) -> Val {
+    let Some((list, func)) = list_func(cfg, GROUP_BY, input) else {
+        return Val::default();
+    };
+    let mut groups: Map<Key, Vec<Val>> = Map::default();
+    for item in list {
+        if cfg.is_aborted() {
+            return Val::default();
+        }
+        let key = func.call(cfg, ctx, item.clone());
+        let Val::Key(key) = key else {
+            return bug!(cfg, "{GROUP_BY}: expected function output to be a key, but got {key}");
+        };
+        groups.entry(key).or_default().push(item);
+    }
+    let groups: Map<Key, Val> =
+        groups.into_iter().map(|(k, v)| (k, Val::List(List::from(v).into()))).collect();
+    Val::Map(groups.into())
+}
+
+// values of the left map win when both maps have the key
+pub fn union(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((mut left, right)) = map_pair(cfg, UNION, input) else {
+        return Val::default();
+    };
+    for (key, value) in right {
+        left.entry(key).or_insert(value);
+    }
+    Val::Map(left.into())
+}
+
+pub fn intersection(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((mut left, right)) = map_pair(cfg, INTERSECTION, input) else {
+        return Val::default();
+    };
+    left.retain(|key, _| right.contains_key(key));
+    Val::Map(left.into())
+}
+
+pub fn difference(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((mut left, right)) = map_pair(cfg, DIFFERENCE, input) else {
+        return Val::default();
+    };
+    left.retain(|key, _| !right.contains_key(key));
+    Val::Map(left.into())
+}
+
+pub fn symmetric_difference(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((mut left, right)) = map_pair(cfg, SYMMETRIC_DIFFERENCE, input) else {
+        return Val::default();
+    };
+    for (key, value) in right {
+        if left.shift_remove(&key).is_none() {
+            left.insert(key, value);
+        }
+    }
+    Val::Map(left.into())
+}
+
+// whether all keys of the left map are in the right map
+pub fn is_subset(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((left, right)) = map_pair(cfg, IS_SUBSET, input) else {
+        return Val::default();
+    };
+    Val::Bit(Bit::from(left.keys().all(|key| right.contains_key(key))))
+}
+
+pub fn get_sorted_keys(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Map(map) = ctx else {
+        return bug!(cfg, "{GET_SORTED_KEYS}: expected context to be a map, but got {ctx}");
+    };
+    let mut keys: Vec<&Key> = map.keys().collect();
+    keys.sort_unstable_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+    let keys: List<Val> = keys.into_iter().map(|k| Val::Key(k.clone())).collect();
+    Val::List(keys.into())
+}
+
+fn map_pair(cfg: &mut Cfg, key: &str, input: Val) -> Option<(Map<Key, Val>, Map<Key, Val>)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{key}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::Map(left) = pair.left else {
+        bug!(cfg, "{key}: expected input.left to be a map, but got {}", pair.left);
+        return None;
+    };
+    let Val::Map(right) = pair.right else {
+        bug!(cfg, "{key}: expected input.right to be a map, but got {}", pair.right);
+        return None;
+    };
+    Some((Map::from(left), Map::from(right)))
+}
+
+fn map_func(cfg: &mut Cfg, key: &str, input: Val) -> Option<(Map<Key, Val>, FuncVal)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{key}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::Map(map) = pair.left else {
+        bug!(cfg, "{key}: expected input.left to be a map, but got {}", pair.left);
+        return None;
+    };
+    let Val::Func(func) = pair.right else {
+        bug!(cfg, "{key}: expected input.right to be a function, but got {}", pair.right);
+        return None;
+    };
+    Some((Map::from(map), func))
+}
@@ -0,0 +1,309 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use const_format::concatcp;
+
+use crate::bug;
+use crate::cfg::CfgMod;
+use crate::cfg::extend_func;
+use crate::cfg::lib::decimal::decimal_config;
+use crate::cfg::utils::dyn_ref;
+use crate::semantics::cfg::Cfg;
+use crate::semantics::core::PREFIX_ID;
+use crate::semantics::ctx::DynCtx;
+use crate::semantics::func::CtxFreeInputEvalFunc;
+use crate::semantics::val::PrimFuncVal;
+use crate::semantics::val::Val;
+use crate::semantics::val::Value;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::Pair;
+use crate::type_::Rational;
+
+#[derive(Clone)]
+pub struct RationalLib {
+    pub make: PrimFuncVal,
+    pub numerator: PrimFuncVal,
+    pub denominator: PrimFuncVal,
+    pub from_integer: PrimFuncVal,
+    pub into_integer: PrimFuncVal,
+    pub from_decimal: PrimFuncVal,
+    pub into_decimal: PrimFuncVal,
+    pub add: PrimFuncVal,
+    pub subtract: PrimFuncVal,
+    pub multiply: PrimFuncVal,
+    pub divide: PrimFuncVal,
+    pub less: PrimFuncVal,
+    pub less_equal: PrimFuncVal,
+    pub greater: PrimFuncVal,
+    pub greater_equal: PrimFuncVal,
+    pub less_greater: PrimFuncVal,
+}
+
+const RATIONAL: &str = "rational";
+
+const TYPE_RATIONAL: &str = concatcp!(PREFIX_ID, RATIONAL);
+
+pub const MAKE: &str = concatcp!(PREFIX_ID, RATIONAL, ".make");
+pub const NUMERATOR: &str = concatcp!(PREFIX_ID, RATIONAL, ".numerator");
+pub const DENOMINATOR: &str = concatcp!(PREFIX_ID, RATIONAL, ".denominator");
+pub const FROM_INTEGER: &str = concatcp!(PREFIX_ID, RATIONAL, ".from_integer");
+pub const INTO_INTEGER: &str = concatcp!(PREFIX_ID, RATIONAL, ".into_integer");
+pub const FROM_DECIMAL: &str = concatcp!(PREFIX_ID, RATIONAL, ".from_decimal");
+pub const INTO_DECIMAL: &str = concatcp!(PREFIX_ID, RATIONAL, ".into_decimal");
+pub const ADD: &str = concatcp!(PREFIX_ID, RATIONAL, ".add");
+pub const SUBTRACT: &str = concatcp!(PREFIX_ID, RATIONAL, ".subtract");
+pub const MULTIPLY: &str = concatcp!(PREFIX_ID, RATIONAL, ".multiply");
+pub const DIVIDE: &str = concatcp!(PREFIX_ID, RATIONAL, ".divide");
+pub const LESS: &str = concatcp!(PREFIX_ID, RATIONAL, ".less");
+pub const LESS_EQUAL: &str = concatcp!(PREFIX_ID, RATIONAL, ".less_equal");
+pub const GREATER: &str = concatcp!(PREFIX_ID, RATIONAL, ".greater");
+pub const GREATER_EQUAL: &str = concatcp!(PREFIX_ID, RATIONAL, ".greater_equal");
+pub const LESS_GREATER: &str = concatcp!(PREFIX_ID, RATIONAL, ".less_greater");
+
+impl Default for RationalLib {
+    fn default() -> Self {
+        RationalLib {
+            make: CtxFreeInputEvalFunc { fn_: make }.build(),
+            numerator: CtxFreeInputEvalFunc { fn_: numerator }.build(),
+            denominator: CtxFreeInputEvalFunc { fn_: denominator }.build(),
+            from_integer: CtxFreeInputEvalFunc { fn_: from_integer }.build(),
+            into_integer: CtxFreeInputEvalFunc { fn_: into_integer }.build(),
+            from_decimal: CtxFreeInputEvalFunc { fn_: from_decimal }.build(),
+            into_decimal: CtxFreeInputEvalFunc { fn_: into_decimal }.build(),
+            add: CtxFreeInputEvalFunc { fn_: add }.build(),
+            subtract: CtxFreeInputEvalFunc { fn_: subtract }.build(),
+            multiply: CtxFreeInputEvalFunc { fn_: multiply }.build(),
+            divide: CtxFreeInputEvalFunc { fn_: divide }.build(),
+            less: CtxFreeInputEvalFunc { fn_: less }.build(),
+            less_equal: CtxFreeInputEvalFunc { fn_: less_equal }.build(),
+            greater: CtxFreeInputEvalFunc { fn_: greater }.build(),
+            greater_equal: CtxFreeInputEvalFunc { fn_: greater_equal }.build(),
+            less_greater: CtxFreeInputEvalFunc { fn_: less_greater }.build(),
+        }
+    }
+}
+
+impl CfgMod for RationalLib {
+    fn extend(self, cfg: &mut Cfg) {
+        extend_func(cfg, MAKE, self.make);
+        extend_func(cfg, NUMERATOR, self.numerator);
+        extend_func(cfg, DENOMINATOR, self.denominator);
+        extend_func(cfg, FROM_INTEGER, self.from_integer);
+        extend_func(cfg, INTO_INTEGER, self.into_integer);
+        extend_func(cfg, FROM_DECIMAL, self.from_decimal);
+        extend_func(cfg, INTO_DECIMAL, self.into_decimal);
+        extend_func(cfg, ADD, self.add);
+        extend_func(cfg, SUBTRACT, self.subtract);
+        extend_func(cfg, MULTIPLY, self.multiply);
+        extend_func(cfg, DIVIDE, self.divide);
+        extend_func(cfg, LESS, self.less);
+        extend_func(cfg, LESS_EQUAL, self.less_equal);
+        extend_func(cfg, GREATER, self.greater);
+        extend_func(cfg, GREATER_EQUAL, self.greater_equal);
+        extend_func(cfg, LESS_GREATER, self.less_greater);
+    }
+}
+
+impl Debug for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let numerator = Val::Int(self.numerator().into());
+        let denominator = Val::Int(self.denominator().into());
+        write!(f, "_ {MAKE} {numerator} : {denominator}")
+    }
+}
+
+// no value can be reached inside
+impl DynCtx<Val, Val> for Rational {
+    fn ref_(&self, cfg: &mut Cfg, key: Val) -> Option<&Val> {
+        bug!(cfg, "context {TYPE_RATIONAL}: value not found for key {key} in {self}");
+        None
+    }
+
+    fn ref_mut(&mut self, cfg: &mut Cfg, key: Val) -> Option<&mut Val> {
+        bug!(cfg, "context {TYPE_RATIONAL}: value not found for key {key} in {self}");
+        None
+    }
+
+    fn set(&mut self, cfg: &mut Cfg, key: Val, _value: Val) -> Option<()> {
+        bug!(cfg, "context {TYPE_RATIONAL}: value not found for key {key} in {self}");
+        None
+    }
+}
+
+impl Value for Rational {
+    fn type_name(&self) -> Key {
+        Key::from_str_unchecked(TYPE_RATIONAL)
+    }
+}
+
+impl From<Rational> for Val {
+    fn from(value: Rational) -> Self {
+        Val::Dyn(Box::new(value))
+    }
+}
+
+// numerator : denominator
+pub fn make(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{MAKE}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Int(numerator) = pair.left else {
+        return bug!(cfg, "{MAKE}: expected input.left to be an integer, but got {}", pair.left);
+    };
+    let Val::Int(denominator) = pair.right else {
+        return bug!(cfg, "{MAKE}: expected input.right to be an integer, but got {}", pair.right);
+    };
+    let Some(r) = Rational::new(numerator.into(), denominator.into()) else {
+        return bug!(cfg, "{MAKE}: expected input.right to be non-zero");
+    };
+    r.into()
+}
+
+pub fn numerator(cfg: &mut Cfg, input: Val) -> Val {
+    let Some(r) = dyn_ref::<Rational>(&input) else {
+        return bug!(cfg, "{NUMERATOR}: expected input to be a rational, but got {input}");
+    };
+    Val::Int(r.numerator().into())
+}
+
+pub fn denominator(cfg: &mut Cfg, input: Val) -> Val {
+    let Some(r) = dyn_ref::<Rational>(&input) else {
+        return bug!(cfg, "{DENOMINATOR}: expected input to be a rational, but got {input}");
+    };
+    Val::Int(r.denominator().into())
+}
+
+pub fn from_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Int(i) = input else {
+        return bug!(cfg, "{FROM_INTEGER}: expected input to be an integer, but got {input}");
+    };
+    Rational::from_int(Int::from(i)).into()
+}
+
+// rounding by the configured decimal rounding mode
+pub fn into_integer(cfg: &mut Cfg, input: Val) -> Val {
+    let Some(r) = dyn_ref::<Rational>(&input) else {
+        return bug!(cfg, "{INTO_INTEGER}: expected input to be a rational, but got {input}");
+    };
+    let r = r.clone();
+    let Some(config) = decimal_config(cfg, INTO_INTEGER) else {
+        return Val::default();
+    };
+    Val::Int(r.to_int(config.rounding_mode()).into())
+}
+
+pub fn from_decimal(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Decimal(d) = input else {
+        return bug!(cfg, "{FROM_DECIMAL}: expected input to be a decimal, but got {input}");
+    };
+    let Some(r) = Rational::from_decimal(&d) else {
+        return bug!(cfg, "{FROM_DECIMAL}: scale of {d} is too large to be supported");
+    };
+    r.into()
+}
+
+// rounding by the configured decimal rounding mode and precision
+pub fn into_decimal(cfg: &mut Cfg, input: Val) -> Val {
+    let Some(r) = dyn_ref::<Rational>(&input) else {
+        return bug!(cfg, "{INTO_DECIMAL}: expected input to be a rational, but got {input}");
+    };
+    let r = r.clone();
+    let Some(config) = decimal_config(cfg, INTO_DECIMAL) else {
+        return Val::default();
+    };
+    let Some(d) = r.to_decimal(config) else {
+        return bug!(cfg, "{INTO_DECIMAL}: precision is too large to be supported");
+    };
+    Val::Decimal(d.into())
+}
+
+pub fn add(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, ADD, input) else {
+        return Val::default();
+    };
+    r1.add(r2).into()
+}
+
+pub fn subtract(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, SUBTRACT, input) else {
+        return Val::default();
+    };
+    r1.subtract(r2).into()
+}
+
+pub fn multiply(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, MULTIPLY, input) else {
+        return Val::default();
+    };
+    r1.multiply(r2).into()
+}
+
+pub fn divide(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, DIVIDE, input) else {
+        return Val::default();
+    };
+    if r2.is_zero() {
+        return bug!(cfg, "{DIVIDE}: expected input.right to be non-zero");
+    }
+    r1.divide(r2).into()
+}
+
+pub fn less(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, LESS, input) else {
+        return Val::default();
+    };
+    Val::Bit(r1.less(&r2))
+}
+
+pub fn less_equal(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, LESS_EQUAL, input) else {
+        return Val::default();
+    };
+    Val::Bit(r1.less_equal(&r2))
+}
+
+pub fn greater(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, GREATER, input) else {
+        return Val::default();
+    };
+    Val::Bit(r1.greater(&r2))
+}
+
+pub fn greater_equal(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, GREATER_EQUAL, input) else {
+        return Val::default();
+    };
+    Val::Bit(r1.greater_equal(&r2))
+}
+
+pub fn less_greater(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((r1, r2)) = rational_pair(cfg, LESS_GREATER, input) else {
+        return Val::default();
+    };
+    Val::Bit(r1.less_greater(&r2))
+}
+
+fn rational_pair(cfg: &mut Cfg, tag: &str, input: Val) -> Option<(Rational, Rational)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let Some(r1) = dyn_ref::<Rational>(&pair.left) else {
+        bug!(cfg, "{tag}: expected input.left to be a rational, but got {}", pair.left);
+        return None;
+    };
+    let Some(r2) = dyn_ref::<Rational>(&pair.right) else {
+        bug!(cfg, "{tag}: expected input.right to be a rational, but got {}", pair.right);
+        return None;
+    };
+    Some((r1.clone(), r2.clone()))
+}
@@ -0,0 +1,236 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use const_format::concatcp;
+use regex::Regex;
+
+use crate::bug;
+use crate::cfg::CfgMod;
+use crate::cfg::extend_func;
+use crate::cfg::utils::dyn_ref;
+use crate::semantics::cfg::Cfg;
+use crate::semantics::core::PREFIX_ID;
+use crate::semantics::ctx::DynCtx;
+use crate::semantics::func::CtxConstInputEvalFunc;
+use crate::semantics::func::CtxFreeInputEvalFunc;
+use crate::semantics::val::PrimFuncVal;
+use crate::semantics::val::Val;
+use crate::semantics::val::Value;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Text;
+
+#[derive(Clone)]
+pub struct RegexLib {
+    pub make: PrimFuncVal,
+    pub is_match: PrimFuncVal,
+    pub find: PrimFuncVal,
+    pub find_all: PrimFuncVal,
+    pub captures: PrimFuncVal,
+    pub replace_all: PrimFuncVal,
+    pub split: PrimFuncVal,
+}
+
+const REGEX: &str = "regex";
+
+const TYPE_REGEX: &str = concatcp!(PREFIX_ID, REGEX);
+
+pub const MAKE: &str = concatcp!(PREFIX_ID, REGEX, ".make");
+pub const IS_MATCH: &str = concatcp!(PREFIX_ID, REGEX, ".is_match");
+pub const FIND: &str = concatcp!(PREFIX_ID, REGEX, ".find");
+pub const FIND_ALL: &str = concatcp!(PREFIX_ID, REGEX, ".find_all");
+pub const CAPTURES: &str = concatcp!(PREFIX_ID, REGEX, ".captures");
+pub const REPLACE_ALL: &str = concatcp!(PREFIX_ID, REGEX, ".replace_all");
+pub const SPLIT: &str = concatcp!(PREFIX_ID, REGEX, ".split");
+
+impl Default for RegexLib {
+    fn default() -> Self {
+        RegexLib {
+            make: CtxFreeInputEvalFunc { fn_: make }.build(),
+            is_match: CtxConstInputEvalFunc { fn_: is_match }.build(),
+            find: CtxConstInputEvalFunc { fn_: find }.build(),
+            find_all: CtxConstInputEvalFunc { fn_: find_all }.build(),
+            captures: CtxConstInputEvalFunc { fn_: captures }.build(),
+            replace_all: CtxConstInputEvalFunc { fn_: replace_all }.build(),
+            split: CtxConstInputEvalFunc { fn_: split }.build(),
+        }
+    }
+}
+
+impl CfgMod for RegexLib {
+    fn extend(self, cfg: &mut Cfg) {
+        extend_func(cfg, MAKE, self.make);
+        extend_func(cfg, IS_MATCH, self.is_match);
+        extend_func(cfg, FIND, self.find);
+        extend_func(cfg, FIND_ALL, self.find_all);
+        extend_func(cfg, CAPTURES, self.captures);
+        extend_func(cfg, REPLACE_ALL, self.replace_all);
+        extend_func(cfg, SPLIT, self.split);
+    }
+}
+
+// a compiled pattern, equal to another one if they have the same pattern
+#[derive(Clone)]
+pub struct RegexVal(Regex);
+
+impl RegexVal {
+    pub fn new(regex: Regex) -> Self {
+        Self(regex)
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl PartialEq for RegexVal {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for RegexVal {}
+
+impl Debug for RegexVal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for RegexVal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "_ {MAKE} {}", Text::from(self.0.as_str()))
+    }
+}
+
+// no value can be reached inside
+impl DynCtx<Val, Val> for RegexVal {
+    fn ref_(&self, cfg: &mut Cfg, key: Val) -> Option<&Val> {
+        bug!(cfg, "context {TYPE_REGEX}: value not found for key {key} in {self}");
+        None
+    }
+
+    fn ref_mut(&mut self, cfg: &mut Cfg, key: Val) -> Option<&mut Val> {
+        bug!(cfg, "context {TYPE_REGEX}: value not found for key {key} in {self}");
+        None
+    }
+
+    fn set(&mut self, cfg: &mut Cfg, key: Val, _value: Val) -> Option<()> {
+        bug!(cfg, "context {TYPE_REGEX}: value not found for key {key} in {self}");
+        None
+    }
+}
+
+impl Value for RegexVal {
+    fn type_name(&self) -> Key {
+        Key::from_str_unchecked(TYPE_REGEX)
+    }
+}
+
+pub fn make(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Text(pattern) = input else {
+        return bug!(cfg, "{MAKE}: expected input to be a text, but got {input}");
+    };
+    let Ok(regex) = Regex::new(&pattern) else {
+        return Val::default();
+    };
+    Val::Dyn(Box::new(RegexVal::new(regex)))
+}
+
+pub fn is_match(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{IS_MATCH}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{IS_MATCH}: expected input to be a text, but got {input}");
+    };
+    Val::Bit(regex.is_match(&text).into())
+}
+
+pub fn find(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{FIND}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{FIND}: expected input to be a text, but got {input}");
+    };
+    let Some(m) = regex.find(&text) else {
+        return Val::default();
+    };
+    Val::Text(Text::from(m.as_str()).into())
+}
+
+pub fn find_all(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{FIND_ALL}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{FIND_ALL}: expected input to be a text, but got {input}");
+    };
+    let list: Vec<Val> =
+        regex.find_iter(&text).map(|m| Val::Text(Text::from(m.as_str()).into())).collect();
+    Val::List(List::from(list).into())
+}
+
+// named groups which don't participate in the match are absent
+pub fn captures(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{CAPTURES}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{CAPTURES}: expected input to be a text, but got {input}");
+    };
+    let Some(captures) = regex.captures(&text) else {
+        return Val::default();
+    };
+    let mut map = Map::default();
+    for name in regex.capture_names().flatten() {
+        if !name.chars().all(Key::is_key) {
+            return bug!(cfg, "{CAPTURES}: expected group name {name} to be a key");
+        }
+        if let Some(m) = captures.name(name) {
+            let value = Val::Text(Text::from(m.as_str()).into());
+            map.insert(Key::from_str_unchecked(name), value);
+        }
+    }
+    Val::Map(map.into())
+}
+
+// replacement text can refer to groups by $name or $index
+pub fn replace_all(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{REPLACE_ALL}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{REPLACE_ALL}: expected input to be a pair, but got {input}");
+    };
+    let Val::Text(text) = &pair.left else {
+        return bug!(cfg, "{REPLACE_ALL}: expected input.left to be a text, but got {}", pair.left);
+    };
+    let Val::Text(replacement) = &pair.right else {
+        return bug!(
+            cfg,
+            "{REPLACE_ALL}: expected input.right to be a text, but got {}",
+            pair.right
+        );
+    };
+    let replaced = regex.replace_all(text, &***replacement);
+    Val::Text(Text::from(replaced.into_owned()).into())
+}
+
+pub fn split(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Some(regex) = regex(ctx) else {
+        return bug!(cfg, "{SPLIT}: expected context to be a regex, but got {ctx}");
+    };
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{SPLIT}: expected input to be a text, but got {input}");
+    };
+    let list: Vec<Val> = regex.split(&text).map(|s| Val::Text(Text::from(s).into())).collect();
+    Val::List(List::from(list).into())
+}
+
+fn regex(val: &Val) -> Option<&Regex> {
+    Some(dyn_ref::<RegexVal>(val)?.regex())
+}
@@ -1,10 +1,14 @@
+use std::fmt::Write;
+
 use const_format::concatcp;
 
 use crate::bug;
 use crate::cfg::CfgMod;
 use crate::cfg::extend_func;
+use crate::cfg::lib::list::to_range;
 use crate::semantics::cfg::Cfg;
 use crate::semantics::core::PREFIX_ID;
+use crate::semantics::func::CtxConstInputEvalFunc;
 use crate::semantics::func::CtxConstInputFreeFunc;
 use crate::semantics::func::CtxFreeInputEvalFunc;
 use crate::semantics::func::CtxMutInputEvalFunc;
@@ -13,6 +17,9 @@ use crate::semantics::val::TEXT;
 use crate::semantics::val::Val;
 use crate::type_::Byte;
 use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Pair;
 use crate::type_::Text;
 
 // todo design add more
@@ -23,6 +30,27 @@ pub struct TextLib {
     pub get_length: PrimFuncVal,
     pub push: PrimFuncVal,
     pub join: PrimFuncVal,
+    pub slice: PrimFuncVal,
+    pub find: PrimFuncVal,
+    pub contain: PrimFuncVal,
+    pub starts_with: PrimFuncVal,
+    pub ends_with: PrimFuncVal,
+    pub split: PrimFuncVal,
+    pub split_whitespace: PrimFuncVal,
+    pub split_lines: PrimFuncVal,
+    pub replace: PrimFuncVal,
+    pub trim: PrimFuncVal,
+    pub trim_start: PrimFuncVal,
+    pub trim_end: PrimFuncVal,
+    pub to_upper_case: PrimFuncVal,
+    pub to_lower_case: PrimFuncVal,
+    pub into_chars: PrimFuncVal,
+    pub less: PrimFuncVal,
+    pub less_equal: PrimFuncVal,
+    pub greater: PrimFuncVal,
+    pub greater_equal: PrimFuncVal,
+    pub less_greater: PrimFuncVal,
+    pub format: PrimFuncVal,
 }
 
 pub const FROM_UTF8: &str = concatcp!(PREFIX_ID, TEXT, ".from_utf8");
@@ -30,6 +58,27 @@ pub const INTO_UTF8: &str = concatcp!(PREFIX_ID, TEXT, ".into_utf8");
 pub const GET_LENGTH: &str = concatcp!(PREFIX_ID, TEXT, ".get_length");
 pub const PUSH: &str = concatcp!(PREFIX_ID, TEXT, ".push");
 pub const JOIN: &str = concatcp!(PREFIX_ID, TEXT, ".join");
+pub const SLICE: &str = concatcp!(PREFIX_ID, TEXT, ".slice");
+pub const FIND: &str = concatcp!(PREFIX_ID, TEXT, ".find");
+pub const CONTAIN: &str = concatcp!(PREFIX_ID, TEXT, ".contain");
+pub const STARTS_WITH: &str = concatcp!(PREFIX_ID, TEXT, ".starts_with");
+pub const ENDS_WITH: &str = concatcp!(PREFIX_ID, TEXT, ".ends_with");
+pub const SPLIT: &str = concatcp!(PREFIX_ID, TEXT, ".split");
+pub const SPLIT_WHITESPACE: &str = concatcp!(PREFIX_ID, TEXT, ".split_whitespace");
+pub const SPLIT_LINES: &str = concatcp!(PREFIX_ID, TEXT, ".split_lines");
+pub const REPLACE: &str = concatcp!(PREFIX_ID, TEXT, ".replace");
+pub const TRIM: &str = concatcp!(PREFIX_ID, TEXT, ".trim");
+pub const TRIM_START: &str = concatcp!(PREFIX_ID, TEXT, ".trim_start");
+pub const TRIM_END: &str = concatcp!(PREFIX_ID, TEXT, ".trim_end");
+pub const TO_UPPER_CASE: &str = concatcp!(PREFIX_ID, TEXT, ".to_upper_case");
+pub const TO_LOWER_CASE: &str = concatcp!(PREFIX_ID, TEXT, ".to_lower_case");
+pub const INTO_CHARS: &str = concatcp!(PREFIX_ID, TEXT, ".into_chars");
+pub const LESS: &str = concatcp!(PREFIX_ID, TEXT, ".less");
+pub const LESS_EQUAL: &str = concatcp!(PREFIX_ID, TEXT, ".less_equal");
+pub const GREATER: &str = concatcp!(PREFIX_ID, TEXT, ".greater");
+pub const GREATER_EQUAL: &str = concatcp!(PREFIX_ID, TEXT, ".greater_equal");
+pub const LESS_GREATER: &str = concatcp!(PREFIX_ID, TEXT, ".less_greater");
+pub const FORMAT: &str = concatcp!(PREFIX_ID, TEXT, ".format");
 
 impl Default for TextLib {
     fn default() -> Self {
@@ -39,6 +88,27 @@ impl Default for TextLib {
             get_length: CtxConstInputFreeFunc { fn_: get_length }.build(),
             push: CtxMutInputEvalFunc { fn_: push }.build(),
             join: CtxFreeInputEvalFunc { fn_: join }.build(),
+            slice: CtxConstInputEvalFunc { fn_: slice }.build(),
+            find: CtxConstInputEvalFunc { fn_: find }.build(),
+            contain: CtxConstInputEvalFunc { fn_: contain }.build(),
+            starts_with: CtxConstInputEvalFunc { fn_: starts_with }.build(),
+            ends_with: CtxConstInputEvalFunc { fn_: ends_with }.build(),
+            split: CtxConstInputEvalFunc { fn_: split }.build(),
+            split_whitespace: CtxConstInputFreeFunc { fn_: split_whitespace }.build(),
+            split_lines: CtxConstInputFreeFunc { fn_: split_lines }.build(),
+            replace: CtxConstInputEvalFunc { fn_: replace }.build(),
+            trim: CtxConstInputFreeFunc { fn_: trim }.build(),
+            trim_start: CtxConstInputFreeFunc { fn_: trim_start }.build(),
+            trim_end: CtxConstInputFreeFunc { fn_: trim_end }.build(),
+            to_upper_case: CtxConstInputFreeFunc { fn_: to_upper_case }.build(),
+            to_lower_case: CtxConstInputFreeFunc { fn_: to_lower_case }.build(),
+            into_chars: CtxFreeInputEvalFunc { fn_: into_chars }.build(),
+            less: CtxFreeInputEvalFunc { fn_: less }.build(),
+            less_equal: CtxFreeInputEvalFunc { fn_: less_equal }.build(),
+            greater: CtxFreeInputEvalFunc { fn_: greater }.build(),
+            greater_equal: CtxFreeInputEvalFunc { fn_: greater_equal }.build(),
+            less_greater: CtxFreeInputEvalFunc { fn_: less_greater }.build(),
+            format: CtxFreeInputEvalFunc { fn_: format }.build(),
         }
     }
 }
@@ -50,6 +120,27 @@ impl CfgMod for TextLib {
         extend_func(cfg, GET_LENGTH, self.get_length);
         extend_func(cfg, PUSH, self.push);
         extend_func(cfg, JOIN, self.join);
+        extend_func(cfg, SLICE, self.slice);
+        extend_func(cfg, FIND, self.find);
+        extend_func(cfg, CONTAIN, self.contain);
+        extend_func(cfg, STARTS_WITH, self.starts_with);
+        extend_func(cfg, ENDS_WITH, self.ends_with);
+        extend_func(cfg, SPLIT, self.split);
+        extend_func(cfg, SPLIT_WHITESPACE, self.split_whitespace);
+        extend_func(cfg, SPLIT_LINES, self.split_lines);
+        extend_func(cfg, REPLACE, self.replace);
+        extend_func(cfg, TRIM, self.trim);
+        extend_func(cfg, TRIM_START, self.trim_start);
+        extend_func(cfg, TRIM_END, self.trim_end);
+        extend_func(cfg, TO_UPPER_CASE, self.to_upper_case);
+        extend_func(cfg, TO_LOWER_CASE, self.to_lower_case);
+        extend_func(cfg, INTO_CHARS, self.into_chars);
+        extend_func(cfg, LESS, self.less);
+        extend_func(cfg, LESS_EQUAL, self.less_equal);
+        extend_func(cfg, GREATER, self.greater);
+        extend_func(cfg, GREATER_EQUAL, self.greater_equal);
+        extend_func(cfg, LESS_GREATER, self.less_greater);
+        extend_func(cfg, FORMAT, self.format);
     }
 }
 
@@ -113,3 +204,293 @@ pub fn join(cfg: &mut Cfg, input: Val) -> Val {
     let text = to_join.join(separator);
     Val::Text(Text::from(text).into())
 }
+
+// range of char indices
+pub fn slice(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{SLICE}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Pair(range) = input else {
+        return bug!(cfg, "{SLICE}: expected input to be a pair, but got {input}");
+    };
+    let range = Pair::from(range);
+    let Some((from, to)) = to_range(cfg, SLICE, range) else {
+        return Val::default();
+    };
+    let len = text.chars().count();
+    let from = from.unwrap_or_default();
+    let to = to.unwrap_or(len);
+    if from > to || to > len {
+        return bug!(cfg, "{SLICE}: range {from} : {to} should be in 0 : {len}");
+    }
+    let slice: String = text.chars().skip(from).take(to - from).collect();
+    Val::Text(Text::from(slice).into())
+}
+
+// char index of the first match
+pub fn find(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{FIND}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Text(pattern) = input else {
+        return bug!(cfg, "{FIND}: expected input to be a text, but got {input}");
+    };
+    let Some(i) = text.find(&**pattern) else {
+        return Val::default();
+    };
+    let i: Int = text[.. i].chars().count().into();
+    Val::Int(i.into())
+}
+
+pub fn contain(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{CONTAIN}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Text(pattern) = input else {
+        return bug!(cfg, "{CONTAIN}: expected input to be a text, but got {input}");
+    };
+    Val::Bit(text.contains(&**pattern).into())
+}
+
+pub fn starts_with(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{STARTS_WITH}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Text(prefix) = input else {
+        return bug!(cfg, "{STARTS_WITH}: expected input to be a text, but got {input}");
+    };
+    Val::Bit(text.starts_with(&**prefix).into())
+}
+
+pub fn ends_with(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{ENDS_WITH}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Text(suffix) = input else {
+        return bug!(cfg, "{ENDS_WITH}: expected input to be a text, but got {input}");
+    };
+    Val::Bit(text.ends_with(&**suffix).into())
+}
+
+pub fn split(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{SPLIT}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Text(separator) = input else {
+        return bug!(cfg, "{SPLIT}: expected input to be a text, but got {input}");
+    };
+    if separator.is_empty() {
+        return bug!(cfg, "{SPLIT}: expected input to be non-empty");
+    }
+    texts(text.split(&**separator))
+}
+
+pub fn split_whitespace(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{SPLIT_WHITESPACE}: expected context to be a text, but got {ctx}");
+    };
+    texts(text.split_whitespace())
+}
+
+pub fn split_lines(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{SPLIT_LINES}: expected context to be a text, but got {ctx}");
+    };
+    texts(text.lines())
+}
+
+fn texts<'a>(iter: impl Iterator<Item = &'a str>) -> Val {
+    let list: Vec<Val> = iter.map(|s| Val::Text(Text::from(s).into())).collect();
+    Val::List(List::from(list).into())
+}
+
+pub fn replace(cfg: &mut Cfg, ctx: &Val, input: Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{REPLACE}: expected context to be a text, but got {ctx}");
+    };
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{REPLACE}: expected input to be a pair, but got {input}");
+    };
+    let Val::Text(from) = &pair.left else {
+        return bug!(cfg, "{REPLACE}: expected input.left to be a text, but got {}", pair.left);
+    };
+    let Val::Text(to) = &pair.right else {
+        return bug!(cfg, "{REPLACE}: expected input.right to be a text, but got {}", pair.right);
+    };
+    if from.is_empty() {
+        return bug!(cfg, "{REPLACE}: expected input.left to be non-empty");
+    }
+    Val::Text(Text::from(text.replace(&***from, to)).into())
+}
+
+pub fn trim(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{TRIM}: expected context to be a text, but got {ctx}");
+    };
+    Val::Text(Text::from(text.trim()).into())
+}
+
+pub fn trim_start(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{TRIM_START}: expected context to be a text, but got {ctx}");
+    };
+    Val::Text(Text::from(text.trim_start()).into())
+}
+
+pub fn trim_end(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{TRIM_END}: expected context to be a text, but got {ctx}");
+    };
+    Val::Text(Text::from(text.trim_end()).into())
+}
+
+pub fn to_upper_case(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{TO_UPPER_CASE}: expected context to be a text, but got {ctx}");
+    };
+    Val::Text(Text::from(text.to_uppercase()).into())
+}
+
+pub fn to_lower_case(cfg: &mut Cfg, ctx: &Val) -> Val {
+    let Val::Text(text) = ctx else {
+        return bug!(cfg, "{TO_LOWER_CASE}: expected context to be a text, but got {ctx}");
+    };
+    Val::Text(Text::from(text.to_lowercase()).into())
+}
+
+pub fn into_chars(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Text(text) = input else {
+        return bug!(cfg, "{INTO_CHARS}: expected input to be a text, but got {input}");
+    };
+    let list: Vec<Val> =
+        text.chars().map(|c| Val::Text(Text::from(c.to_string()).into())).collect();
+    Val::List(List::from(list).into())
+}
+
+pub fn less(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((t1, t2)) = text_pair(cfg, LESS, input) else {
+        return Val::default();
+    };
+    Val::Bit(t1.less(&t2))
+}
+
+pub fn less_equal(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((t1, t2)) = text_pair(cfg, LESS_EQUAL, input) else {
+        return Val::default();
+    };
+    Val::Bit(t1.less_equal(&t2))
+}
+
+pub fn greater(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((t1, t2)) = text_pair(cfg, GREATER, input) else {
+        return Val::default();
+    };
+    Val::Bit(t1.greater(&t2))
+}
+
+pub fn greater_equal(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((t1, t2)) = text_pair(cfg, GREATER_EQUAL, input) else {
+        return Val::default();
+    };
+    Val::Bit(t1.greater_equal(&t2))
+}
+
+pub fn less_greater(cfg: &mut Cfg, input: Val) -> Val {
+    let Some((t1, t2)) = text_pair(cfg, LESS_GREATER, input) else {
+        return Val::default();
+    };
+    Val::Bit(t1.less_greater(&t2))
+}
+
+// template {
+// {name} for a value in a map, {index} or {} for the next value in a list
+// {name:pretty} or {name:compact} to generate the value in air syntax
+// otherwise texts are inserted as is and other values are generated compactly
+// {{ and }} for literal braces
+// } template
+pub fn format(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{FORMAT}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::Text(template) = pair.left else {
+        return bug!(cfg, "{FORMAT}: expected input.left to be a text, but got {}", pair.left);
+    };
+    if !matches!(pair.right, Val::Map(_) | Val::List(_)) {
+        return bug!(
+            cfg,
+            "{FORMAT}: expected input.right to be a map or a list, but got {}",
+            pair.right
+        );
+    }
+    let mut output = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest: &str = &template;
+    while let Some(i) = rest.find(['{', '}']) {
+        output.push_str(&rest[.. i]);
+        let brace = &rest[i ..];
+        if brace.starts_with("{{") || brace.starts_with("}}") {
+            output.push_str(&brace[.. 1]);
+            rest = &brace[2 ..];
+            continue;
+        }
+        if brace.starts_with('}') {
+            return bug!(cfg, "{FORMAT}: unmatched }} in template {}", &**template);
+        }
+        let Some(end) = brace.find('}') else {
+            return bug!(cfg, "{FORMAT}: unmatched {{ in template {}", &**template);
+        };
+        let placeholder = &brace[1 .. end];
+        rest = &brace[end + 1 ..];
+        let (name, style) = match placeholder.split_once(':') {
+            Some((name, style)) => (name, Some(style)),
+            None => (placeholder, None),
+        };
+        let value = match &pair.right {
+            Val::Map(map) => map.get(&Key::from_str_unchecked(name)),
+            Val::List(list) => {
+                // only implicit placeholders take the next item
+                let index = if name.is_empty() {
+                    next += 1;
+                    Some(next - 1)
+                } else {
+                    name.parse().ok()
+                };
+                index.and_then(|index: usize| list.get(index))
+            },
+            _ => unreachable!(),
+        };
+        let Some(value) = value else {
+            return bug!(cfg, "{FORMAT}: no value for placeholder {{{placeholder}}}");
+        };
+        match (style, value) {
+            (None, Val::Text(text)) => output.push_str(text),
+            (None | Some("compact"), value) => output.push_str(&value.to_string()),
+            (Some("pretty"), value) => {
+                let _ = write!(output, "{value:#}");
+            },
+            (Some(style), _) => {
+                return bug!(cfg, "{FORMAT}: unknown style {style} in {{{placeholder}}}");
+            },
+        }
+    }
+    output.push_str(rest);
+    Val::Text(Text::from(output).into())
+}
+
+fn text_pair(cfg: &mut Cfg, tag: &str, input: Val) -> Option<(Text, Text)> {
+    let Val::Pair(pair) = input else {
+        bug!(cfg, "{tag}: expected input to be a pair, but got {input}");
+        return None;
+    };
+    let pair = Pair::from(pair);
+    let Val::Text(t1) = pair.left else {
+        bug!(cfg, "{tag}: expected input.left to be a text, but got {}", pair.left);
+        return None;
+    };
+    let Val::Text(t2) = pair.right else {
+        bug!(cfg, "{tag}: expected input.right to be a text, but got {}", pair.right);
+        return None;
+    };
+    Some((t1.into(), t2.into()))
+}
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
@@ -5,9 +6,13 @@ use std::time::UNIX_EPOCH;
 use const_format::concatcp;
 
 pub use self::arbitrary::Arbitrary;
+pub use self::diff::Change;
+pub use self::diff::diff_val;
+pub use self::diff::patch_val;
 
 _____!();
 
+use bigdecimal::BigDecimal;
 use rand::SeedableRng;
 use rand::prelude::SmallRng;
 
@@ -56,6 +61,9 @@ pub struct ValueLib {
     pub any: PrimFuncVal,
     pub get_type: PrimFuncVal,
     pub equal: PrimFuncVal,
+    pub compare: PrimFuncVal,
+    pub diff: PrimFuncVal,
+    pub patch: PrimFuncVal,
 }
 
 const VALUE: &str = "value";
@@ -63,6 +71,9 @@ const VALUE: &str = "value";
 pub const ANY: &str = concatcp!(PREFIX_ID, VALUE, ".any");
 pub const GET_TYPE: &str = concatcp!(PREFIX_ID, VALUE, ".get_type");
 pub const EQUAL: &str = concatcp!(PREFIX_ID, VALUE, ".equal");
+pub const COMPARE: &str = concatcp!(PREFIX_ID, VALUE, ".compare");
+pub const DIFF: &str = concatcp!(PREFIX_ID, VALUE, ".diff");
+pub const PATCH: &str = concatcp!(PREFIX_ID, VALUE, ".patch");
 
 impl Default for ValueLib {
     fn default() -> Self {
@@ -70,6 +81,9 @@ impl Default for ValueLib {
             any: CtxFreeInputEvalFunc { fn_: any }.build(),
             get_type: CtxConstInputFreeFunc { fn_: get_type }.build(),
             equal: CtxFreeInputEvalFunc { fn_: equal }.build(),
+            compare: CtxFreeInputEvalFunc { fn_: compare }.build(),
+            diff: CtxFreeInputEvalFunc { fn_: diff }.build(),
+            patch: CtxFreeInputEvalFunc { fn_: patch }.build(),
         }
     }
 }
@@ -79,6 +93,9 @@ impl CfgMod for ValueLib {
         extend_func(cfg, ANY, self.any);
         extend_func(cfg, GET_TYPE, self.get_type);
         extend_func(cfg, EQUAL, self.equal);
+        extend_func(cfg, COMPARE, self.compare);
+        extend_func(cfg, DIFF, self.diff);
+        extend_func(cfg, PATCH, self.patch);
     }
 }
 
@@ -159,4 +176,128 @@ pub fn equal(cfg: &mut Cfg, input: Val) -> Val {
     Val::Bit(Bit::from(pair.left == pair.right))
 }
 
+// returns -1, 0 or 1
+pub fn compare(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{COMPARE}: expected input to be a pair, but got {input}");
+    };
+    let Some(ordering) = compare_val(&pair.left, &pair.right) else {
+        return bug!(cfg, "{COMPARE}: expected input to be data, but got {}", Val::Pair(pair));
+    };
+    Val::Int(Int::from(ordering as i8).into())
+}
+
+pub fn diff(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{DIFF}: expected input to be a pair, but got {input}");
+    };
+    let changes: List<Val> = diff_val(&pair.left, &pair.right).into_iter().map(Val::from).collect();
+    Val::List(changes.into())
+}
+
+// the input is value : changes
+pub fn patch(cfg: &mut Cfg, input: Val) -> Val {
+    let Val::Pair(pair) = input else {
+        return bug!(cfg, "{PATCH}: expected input to be a pair, but got {input}");
+    };
+    let pair = Pair::from(pair);
+    let Val::List(changes) = pair.right else {
+        return bug!(cfg, "{PATCH}: expected input.right to be a list, but got {}", pair.right);
+    };
+    let mut val = pair.left;
+    let mut parsed = Vec::with_capacity(changes.len());
+    for change in List::from(changes) {
+        match Change::try_from(change) {
+            Ok(change) => parsed.push(change),
+            Err(change) => return bug!(cfg, "{PATCH}: expected a change, but got {change}"),
+        }
+    }
+    if patch_val(cfg, &mut val, parsed).is_none() {
+        return Val::default();
+    }
+    val
+}
+
+// total order of data, returns none if any of the values is not data
+// values of different types are ordered by type:
+// unit < bit < key < text < int and decimal < byte < cell < pair < call < list < map
+// - false < true
+// - keys and texts are compared by unicode code points
+// - ints and decimals are compared numerically, an int is less than an equal decimal
+// - bytes and lists are compared lexicographically
+// - cells, pairs and calls are compared by their parts from left to right
+// - maps are compared lexicographically as lists of entries sorted by keys
+pub fn compare_val(left: &Val, right: &Val) -> Option<Ordering> {
+    let ordering = match (left, right) {
+        (Val::Unit(_), Val::Unit(_)) => Ordering::Equal,
+        (Val::Bit(left), Val::Bit(right)) => left.cmp(right),
+        (Val::Key(left), Val::Key(right)) => (**left).cmp(&**right),
+        (Val::Text(left), Val::Text(right)) => (***left).cmp(&***right),
+        (Val::Int(left), Val::Int(right)) => (***left).cmp(&***right),
+        (Val::Decimal(left), Val::Decimal(right)) => (***left).cmp(&***right),
+        (Val::Int(left), Val::Decimal(right)) => {
+            int_to_decimal(left).cmp(right).then(Ordering::Less)
+        },
+        (Val::Decimal(left), Val::Int(right)) => {
+            (***left).cmp(&int_to_decimal(right)).then(Ordering::Greater)
+        },
+        (Val::Byte(left), Val::Byte(right)) => (***left).cmp(&***right),
+        (Val::Cell(left), Val::Cell(right)) => compare_val(&left.value, &right.value)?,
+        (Val::Pair(left), Val::Pair(right)) => compare_val(&left.left, &right.left)?
+            .then(compare_val(&left.right, &right.right)?),
+        (Val::Call(left), Val::Call(right)) => compare_val(&left.func, &right.func)?
+            .then(compare_val(&left.input, &right.input)?),
+        (Val::List(left), Val::List(right)) => compare_list(left, right)?,
+        (Val::Map(left), Val::Map(right)) => {
+            let mut left: Vec<_> = left.iter().collect();
+            let mut right: Vec<_> = right.iter().collect();
+            left.sort_unstable_by(|(k1, _), (k2, _)| k1.as_bytes().cmp(k2.as_bytes()));
+            right.sort_unstable_by(|(k1, _), (k2, _)| k1.as_bytes().cmp(k2.as_bytes()));
+            for ((k1, v1), (k2, v2)) in left.iter().zip(right.iter()) {
+                let ordering = k1.as_bytes().cmp(k2.as_bytes()).then(compare_val(v1, v2)?);
+                if ordering.is_ne() {
+                    return Some(ordering);
+                }
+            }
+            left.len().cmp(&right.len())
+        },
+        (left, right) => type_rank(left)?.cmp(&type_rank(right)?),
+    };
+    Some(ordering)
+}
+
+fn compare_list(left: &[Val], right: &[Val]) -> Option<Ordering> {
+    for (left, right) in left.iter().zip(right) {
+        let ordering = compare_val(left, right)?;
+        if ordering.is_ne() {
+            return Some(ordering);
+        }
+    }
+    Some(left.len().cmp(&right.len()))
+}
+
+fn int_to_decimal(int: &Int) -> BigDecimal {
+    BigDecimal::from_bigint((**int).clone(), 0)
+}
+
+fn type_rank(val: &Val) -> Option<u8> {
+    let rank = match val {
+        Val::Unit(_) => 0,
+        Val::Bit(_) => 1,
+        Val::Key(_) => 2,
+        Val::Text(_) => 3,
+        Val::Int(_) | Val::Decimal(_) => 4,
+        Val::Byte(_) => 5,
+        Val::Cell(_) => 6,
+        Val::Pair(_) => 7,
+        Val::Call(_) => 8,
+        Val::List(_) => 9,
+        Val::Map(_) => 10,
+        _ => return None,
+    };
+    Some(rank)
+}
+
 mod arbitrary;
+
+mod diff;
@@ -0,0 +1,319 @@
+use num_traits::ToPrimitive;
+
+use crate::bug;
+use crate::cfg::lib::value::PATCH;
+use crate::semantics::cfg::Cfg;
+use crate::semantics::core::FUNCTION;
+use crate::semantics::core::INPUT;
+use crate::semantics::core::LEFT;
+use crate::semantics::core::RIGHT;
+use crate::semantics::core::VALUE;
+use crate::semantics::ctx::DynCtx;
+use crate::semantics::val::Val;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+
+// a change of a value at a path
+// the path is a list of steps, which are keys of maps, indexes of lists,
+// `_left` and `_right` of pairs, `_value` of cells and `_function` and `_input` of calls
+// indexes of lists are positions after the earlier changes in the list are applied
+#[derive(Clone, PartialEq, Eq)]
+pub enum Change {
+    // an entry added to a map or an item inserted into a list
+    Add { path: List<Val>, new: Val },
+    // an entry removed from a map or an item removed from a list
+    Remove { path: List<Val>, old: Val },
+    Replace { path: List<Val>, old: Val, new: Val },
+}
+
+const PATH: &str = "path";
+const OLD: &str = "old";
+const NEW: &str = "new";
+
+// {path : [..], new : ..}, {path : [..], old : ..} or {path : [..], old : .., new : ..}
+impl From<Change> for Val {
+    fn from(change: Change) -> Self {
+        let mut map = Map::default();
+        let (path, old, new) = match change {
+            Change::Add { path, new } => (path, None, Some(new)),
+            Change::Remove { path, old } => (path, Some(old), None),
+            Change::Replace { path, old, new } => (path, Some(old), Some(new)),
+        };
+        map.insert(Key::from_str_unchecked(PATH), Val::List(path.into()));
+        if let Some(old) = old {
+            map.insert(Key::from_str_unchecked(OLD), old);
+        }
+        if let Some(new) = new {
+            map.insert(Key::from_str_unchecked(NEW), new);
+        }
+        Val::Map(map.into())
+    }
+}
+
+impl TryFrom<Val> for Change {
+    type Error = Val;
+    fn try_from(val: Val) -> Result<Self, Self::Error> {
+        let Val::Map(map) = val else {
+            return Err(val);
+        };
+        let mut map = Map::from(map);
+        let Some(Val::List(path)) = map.shift_remove(&Key::from_str_unchecked(PATH)) else {
+            return Err(Val::Map(map.into()));
+        };
+        let path = List::from(path);
+        let old = map.shift_remove(&Key::from_str_unchecked(OLD));
+        let new = map.shift_remove(&Key::from_str_unchecked(NEW));
+        if !map.is_empty() {
+            return Err(Val::Map(map.into()));
+        }
+        let change = match (old, new) {
+            (None, Some(new)) => Change::Add { path, new },
+            (Some(old), None) => Change::Remove { path, old },
+            (Some(old), Some(new)) => Change::Replace { path, old, new },
+            (None, None) => return Err(Val::Map(map.into())),
+        };
+        Ok(change)
+    }
+}
+
+// changes which turn the left value into the right value
+// maps, lists, pairs, cells and calls are compared by their parts,
+// other values are replaced as a whole
+// items of lists are aligned by their longest common subsequence before compared
+pub fn diff_val(left: &Val, right: &Val) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff(left, right, &mut Vec::new(), &mut changes);
+    changes
+}
+
+fn diff(left: &Val, right: &Val, path: &mut Vec<Val>, changes: &mut Vec<Change>) {
+    if left == right {
+        return;
+    }
+    match (left, right) {
+        (Val::Map(left), Val::Map(right)) => {
+            for (key, old) in left.iter() {
+                path.push(Val::Key(key.clone()));
+                match right.get(key) {
+                    Some(new) => diff(old, new, path, changes),
+                    None => changes.push(Change::Remove { path: to_path(path), old: old.clone() }),
+                }
+                path.pop();
+            }
+            for (key, new) in right.iter() {
+                if left.contains_key(key) {
+                    continue;
+                }
+                path.push(Val::Key(key.clone()));
+                changes.push(Change::Add { path: to_path(path), new: new.clone() });
+                path.pop();
+            }
+        },
+        (Val::List(left), Val::List(right)) => diff_list(left, right, path, changes),
+        (Val::Pair(left), Val::Pair(right)) => {
+            diff_part(LEFT, &left.left, &right.left, path, changes);
+            diff_part(RIGHT, &left.right, &right.right, path, changes);
+        },
+        (Val::Cell(left), Val::Cell(right)) => {
+            diff_part(VALUE, &left.value, &right.value, path, changes);
+        },
+        (Val::Call(left), Val::Call(right)) => {
+            diff_part(FUNCTION, &left.func, &right.func, path, changes);
+            diff_part(INPUT, &left.input, &right.input, path, changes);
+        },
+        (left, right) => {
+            let path = to_path(path);
+            changes.push(Change::Replace { path, old: left.clone(), new: right.clone() });
+        },
+    }
+}
+
+// items are aligned by their longest common subsequence,
+// unaligned items between aligned ones are diffed by position, then the rest are removed or added
+fn diff_list(left: &[Val], right: &[Val], path: &mut Vec<Val>, changes: &mut Vec<Change>) {
+    let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+    let (left, right) = (&left[prefix ..], &right[prefix ..]);
+    let suffix = left.iter().rev().zip(right.iter().rev()).take_while(|(l, r)| l == r).count();
+    let left = &left[.. left.len() - suffix];
+    let right = &right[.. right.len() - suffix];
+    let (mut i, mut j) = (0, 0);
+    for (aligned_i, aligned_j) in lcs(left, right).into_iter().chain([(left.len(), right.len())]) {
+        let old = &left[i .. aligned_i];
+        let new = &right[j .. aligned_j];
+        // items before are already changed, so indexes are positions in the right list
+        let start = prefix + j;
+        let common = old.len().min(new.len());
+        for k in 0 .. common {
+            path.push(index(start + k));
+            diff(&old[k], &new[k], path, changes);
+            path.pop();
+        }
+        path.push(index(start + common));
+        for old in &old[common ..] {
+            changes.push(Change::Remove { path: to_path(path), old: old.clone() });
+        }
+        path.pop();
+        for (k, new) in new.iter().enumerate().skip(common) {
+            path.push(index(start + k));
+            changes.push(Change::Add { path: to_path(path), new: new.clone() });
+            path.pop();
+        }
+        (i, j) = (aligned_i + 1, aligned_j + 1);
+    }
+}
+
+// lists longer than this are diffed by position instead of aligned
+const MAX_LCS_SIZE: usize = 1 << 20;
+
+// index pairs of equal items in the longest common subsequence, in order
+fn lcs(left: &[Val], right: &[Val]) -> Vec<(usize, usize)> {
+    if left.len().saturating_mul(right.len()) > MAX_LCS_SIZE {
+        return Vec::new();
+    }
+    // lengths[i * width + j] is the length of the lcs of left[i ..] and right[j ..]
+    let width = right.len() + 1;
+    let mut lengths = vec![0usize; (left.len() + 1) * width];
+    for i in (0 .. left.len()).rev() {
+        for j in (0 .. right.len()).rev() {
+            lengths[i * width + j] = if left[i] == right[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn diff_part(step: &str, left: &Val, right: &Val, path: &mut Vec<Val>, changes: &mut Vec<Change>) {
+    path.push(Val::Key(Key::from_str_unchecked(step)));
+    diff(left, right, path, changes);
+    path.pop();
+}
+
+fn index(i: usize) -> Val {
+    Val::Int(Int::from(i).into())
+}
+
+fn to_path(path: &[Val]) -> List<Val> {
+    List::from(path.to_owned())
+}
+
+// apply changes in order, the old values in changes should match the current values
+pub fn patch_val(cfg: &mut Cfg, val: &mut Val, changes: Vec<Change>) -> Option<()> {
+    for change in changes {
+        patch(cfg, val, change)?;
+    }
+    Some(())
+}
+
+fn patch(cfg: &mut Cfg, val: &mut Val, change: Change) -> Option<()> {
+    match change {
+        Change::Add { path, new } => {
+            let (parent, last) = split_path(cfg, val, path)?;
+            add(cfg, parent, last, new)
+        },
+        Change::Remove { path, old } => {
+            let (parent, last) = split_path(cfg, val, path)?;
+            remove(cfg, parent, last, old)
+        },
+        Change::Replace { path, old, new } => {
+            let mut current = val;
+            for step in path {
+                current = current.ref_mut(cfg, step)?;
+            }
+            if *current != old {
+                bug!(cfg, "{PATCH}: expected value to be {old}, but got {current}");
+                return None;
+            }
+            *current = new;
+            Some(())
+        },
+    }
+}
+
+fn split_path<'a>(
+    cfg: &mut Cfg, val: &'a mut Val, path: List<Val>,
+) -> Option<(&'a mut Val, Val)> {
+    let mut path = Vec::from(path);
+    let Some(last) = path.pop() else {
+        bug!(cfg, "{PATCH}: expected path to be non-empty");
+        return None;
+    };
+    let mut parent = val;
+    for step in path {
+        parent = parent.ref_mut(cfg, step)?;
+    }
+    Some((parent, last))
+}
+
+fn add(cfg: &mut Cfg, parent: &mut Val, step: Val, new: Val) -> Option<()> {
+    match (parent, step) {
+        (Val::Map(map), Val::Key(key)) => {
+            if map.contains_key(&key) {
+                bug!(cfg, "{PATCH}: expected key {key} to be absent in the map");
+                return None;
+            }
+            map.insert(key, new);
+        },
+        (Val::List(list), Val::Int(i)) => {
+            let Some(i) = i.to_usize().filter(|i| *i <= list.len()) else {
+                bug!(cfg, "{PATCH}: index {i} should <= list.len {}", list.len());
+                return None;
+            };
+            list.insert(i, new);
+        },
+        (parent, step) => {
+            bug!(cfg, "{PATCH}: unable to add {step} to {parent}");
+            return None;
+        },
+    }
+    Some(())
+}
+
+fn remove(cfg: &mut Cfg, parent: &mut Val, step: Val, old: Val) -> Option<()> {
+    match (parent, step) {
+        (Val::Map(map), Val::Key(key)) => {
+            let Some(current) = map.get(&key) else {
+                bug!(cfg, "{PATCH}: value not found for key {key} in the map");
+                return None;
+            };
+            if *current != old {
+                bug!(cfg, "{PATCH}: expected value to be {old}, but got {current}");
+                return None;
+            }
+            map.shift_remove(&key);
+        },
+        (Val::List(list), Val::Int(i)) => {
+            let Some(i) = i.to_usize().filter(|i| *i < list.len()) else {
+                bug!(cfg, "{PATCH}: index {i} should < list.len {}", list.len());
+                return None;
+            };
+            if list[i] != old {
+                bug!(cfg, "{PATCH}: expected value to be {old}, but got {}", list[i]);
+                return None;
+            }
+            list.remove(i);
+        },
+        (parent, step) => {
+            bug!(cfg, "{PATCH}: unable to remove {step} from {parent}");
+            return None;
+        },
+    }
+    Some(())
+}
@@ -32,6 +32,12 @@ pub struct CorePrelude {
     pub decimal_greater_equal: PrimFuncVal,
     pub decimal_less_greater: PrimFuncVal,
 
+    pub text_split: PrimFuncVal,
+    pub text_replace: PrimFuncVal,
+    pub text_trim: PrimFuncVal,
+    pub text_starts_with: PrimFuncVal,
+    pub text_ends_with: PrimFuncVal,
+
     pub call: PrimFuncVal,
 
     pub move_: PrimFuncVal,
@@ -102,6 +108,12 @@ impl CorePrelude {
             decimal_greater_equal: lib.decimal.greater_equal.clone(),
             decimal_less_greater: lib.decimal.less_greater.clone(),
 
+            text_split: lib.text.split.clone(),
+            text_replace: lib.text.replace.clone(),
+            text_trim: lib.text.trim.clone(),
+            text_starts_with: lib.text.starts_with.clone(),
+            text_ends_with: lib.text.ends_with.clone(),
+
             call: lib.call.make.clone(),
 
             move_: lib.map.move_.clone(),
@@ -169,6 +181,12 @@ impl Prelude for CorePrelude {
         map_put_func(map, ">=.", &self.decimal_greater_equal);
         map_put_func(map, "<>.", &self.decimal_less_greater);
 
+        map_put_func(map, "split", &self.text_split);
+        map_put_func(map, "replace", &self.text_replace);
+        map_put_func(map, "trim", &self.text_trim);
+        map_put_func(map, "starts_with", &self.text_starts_with);
+        map_put_func(map, "ends_with", &self.text_ends_with);
+
         map_put_func(map, "call", &self.call);
 
         map_put_func(map, "move", &self.move_);
@@ -22,6 +22,7 @@ use crate::syntax::FmtRepr;
 use crate::syntax::ParseError;
 use crate::syntax::ParseRepr;
 use crate::syntax::parse;
+use crate::syntax::repr::Repr;
 use crate::type_::Bit;
 use crate::type_::Byte;
 use crate::type_::Call;
@@ -37,6 +38,77 @@ use crate::type_::Unit;
 
 impl ParseRepr for Val {}
 
+impl From<Repr> for Val {
+    fn from(repr: Repr) -> Self {
+        match repr {
+            Repr::Unit(unit) => Val::Unit(unit),
+            Repr::Bit(bit) => Val::Bit(bit),
+            Repr::Key(key) => Val::Key(key),
+            Repr::Text(text) => Val::Text(text.into()),
+            Repr::Int(int) => Val::Int(int.into()),
+            Repr::Decimal(decimal) => Val::Decimal(decimal.into()),
+            Repr::Byte(byte) => Val::Byte(byte.into()),
+            Repr::Cell(cell) => Val::Cell(Cell::new(Val::from(cell.value)).into()),
+            Repr::Pair(pair) => {
+                let pair = Pair::new(Val::from(pair.left), Val::from(pair.right));
+                Val::Pair(pair.into())
+            },
+            Repr::Call(call) => {
+                let call = Call::new(Val::from(call.func), Val::from(call.input));
+                Val::Call(call.into())
+            },
+            Repr::List(list) => {
+                let list: List<Val> = list.into_iter().map(Val::from).collect();
+                Val::List(list.into())
+            },
+            Repr::Map(map) => {
+                let map: Map<Key, Val> = map.into_iter().map(|(k, v)| (k, Val::from(v))).collect();
+                Val::Map(map.into())
+            },
+        }
+    }
+}
+
+// only data can be converted, the first value which is not data is returned as the error
+impl TryFrom<Val> for Repr {
+    type Error = Val;
+    fn try_from(val: Val) -> Result<Self, Self::Error> {
+        let repr = match val {
+            Val::Unit(unit) => Repr::Unit(unit),
+            Val::Bit(bit) => Repr::Bit(bit),
+            Val::Key(key) => Repr::Key(key),
+            Val::Text(text) => Repr::Text(text.into()),
+            Val::Int(int) => Repr::Int(int.into()),
+            Val::Decimal(decimal) => Repr::Decimal(decimal.into()),
+            Val::Byte(byte) => Repr::Byte(byte.into()),
+            Val::Cell(cell) => {
+                let cell = Cell::from(cell);
+                Repr::Cell(Box::new(Cell::new(Repr::try_from(cell.value)?)))
+            },
+            Val::Pair(pair) => {
+                let pair = Pair::from(pair);
+                let pair = Pair::new(Repr::try_from(pair.left)?, Repr::try_from(pair.right)?);
+                Repr::Pair(Box::new(pair))
+            },
+            Val::Call(call) => {
+                let call = Call::from(call);
+                let call = Call::new(Repr::try_from(call.func)?, Repr::try_from(call.input)?);
+                Repr::Call(Box::new(call))
+            },
+            Val::List(list) => {
+                let list = List::from(list).into_iter().map(Repr::try_from);
+                Repr::List(list.collect::<Result<List<Repr>, Val>>()?)
+            },
+            Val::Map(map) => {
+                let map = Map::from(map).into_iter().map(|(k, v)| Ok((k, Repr::try_from(v)?)));
+                Repr::Map(map.collect::<Result<Map<Key, Repr>, Val>>()?)
+            },
+            val => return Err(val),
+        };
+        Ok(repr)
+    }
+}
+
 impl FromStr for Val {
     type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -86,6 +158,14 @@ impl FmtRepr for Val {
         matches!(self, Val::Pair(_))
     }
 
+    fn is_list(&self) -> bool {
+        matches!(self, Val::List(_))
+    }
+
+    fn is_map(&self) -> bool {
+        matches!(self, Val::Map(_))
+    }
+
     fn to_pair(&self) -> Pair<&dyn FmtRepr, &dyn FmtRepr> {
         let Val::Pair(pair) = self else { panic!("called `FmtRepr::to_pair()` on non-pair value") };
         Pair::new(&pair.left, &pair.right)
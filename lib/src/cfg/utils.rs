@@ -1,11 +1,22 @@
+use std::any::Any;
+
 use crate::semantics::val::MapVal;
 use crate::semantics::val::Val;
 use crate::type_::Key;
 
 pub(crate) fn map_remove(map: &mut MapVal, name: &str) -> Val {
-    map.remove(&Key::from_str_unchecked(name)).unwrap_or_default()
+    map.swap_remove(&Key::from_str_unchecked(name)).unwrap_or_default()
 }
 
 pub(crate) fn key(s: &str) -> Val {
     Val::Key(Key::from_str_unchecked(s))
 }
+
+// the value of type T inside a dynamic value
+pub(crate) fn dyn_ref<T: 'static>(val: &Val) -> Option<&T> {
+    let Val::Dyn(val) = val else {
+        return None;
+    };
+    let val: &dyn Any = &**val;
+    val.downcast_ref::<T>()
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "serde")]
+pub use self::serde_::from_str;
+#[cfg(feature = "serde")]
+pub use self::serde_::to_string;
+
 // https://github.com/rust-lang/rustfmt/issues/4070
 #[macro_export]
 macro_rules! _____ {
@@ -18,6 +23,10 @@ pub mod syntax;
 
 pub mod type_;
 
+// use semantics, syntax, type
+#[cfg(feature = "serde")]
+pub mod serde_;
+
 pub mod trait_;
 
 #[expect(dead_code)]
@@ -1,8 +1,7 @@
-use std::collections::hash_map::Entry;
-
 use const_format::concatcp;
 use derive_more::Deref;
 use derive_more::DerefMut;
+use indexmap::map::Entry;
 
 use crate::semantics::core::PREFIX_ID;
 use crate::semantics::val::Val;
@@ -5,6 +5,12 @@ pub use self::key::PREFIX_CTX;
 pub use self::key::PREFIX_ID;
 pub use self::key::PREFIX_SHIFT;
 
+pub(crate) use self::ctx::FUNCTION;
+pub(crate) use self::ctx::INPUT;
+pub(crate) use self::ctx::LEFT;
+pub(crate) use self::ctx::RIGHT;
+pub(crate) use self::ctx::VALUE;
+
 _____!();
 
 use crate::semantics::cfg::Cfg;
@@ -0,0 +1,39 @@
+pub use self::de::from_repr;
+pub use self::de::from_str;
+pub use self::error::SerdeError;
+pub use self::ser::ReprSerializer;
+pub use self::ser::to_repr;
+pub use self::ser::to_string;
+
+_____!();
+
+use crate::type_::Key;
+
+// air specific types are serialized as newtype structs with these names,
+// so other data formats see through them
+const KEY: &str = "$airlang::key";
+const INT: &str = "$airlang::integer";
+const DECIMAL: &str = "$airlang::decimal";
+const CELL: &str = "$airlang::cell";
+const PAIR: &str = "$airlang::pair";
+const CALL: &str = "$airlang::call";
+// deserialize a repr losslessly from its source
+const REPR: &str = "$airlang::repr";
+
+fn key(s: &str) -> Result<Key, SerdeError> {
+    if !s.chars().all(Key::is_key) {
+        return Err(SerdeError::new(format!("expected a key, but got {s:?}")));
+    }
+    Ok(Key::from_str_unchecked(s))
+}
+
+mod error;
+
+mod ser;
+
+mod de;
+
+mod value;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,339 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::Deserializer;
+use serde::de::DeserializeOwned;
+use serde::de::DeserializeSeed;
+use serde::de::EnumAccess;
+use serde::de::IntoDeserializer;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::VariantAccess;
+use serde::de::Visitor;
+use serde::de::value::StringDeserializer;
+use serde::forward_to_deserialize_any;
+
+use super::REPR;
+use super::SerdeError;
+use crate::syntax::repr::Repr;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::Map;
+
+pub fn from_repr<T: DeserializeOwned>(repr: Repr) -> Result<T, SerdeError> {
+    T::deserialize(repr)
+}
+
+pub fn from_str<T: DeserializeOwned>(src: &str) -> Result<T, SerdeError> {
+    let repr: Repr = src.parse()?;
+    from_repr(repr)
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Repr {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn int<'de, V: Visitor<'de>>(int: Int, visitor: V) -> Result<V::Value, SerdeError> {
+    if let Some(i) = int.to_i64() {
+        return visitor.visit_i64(i);
+    }
+    if let Some(u) = int.to_u64() {
+        return visitor.visit_u64(u);
+    }
+    if let Some(i) = int.to_i128() {
+        return visitor.visit_i128(i);
+    }
+    if let Some(u) = int.to_u128() {
+        return visitor.visit_u128(u);
+    }
+    Err(SerdeError::new(format!("integer {int} out of range")))
+}
+
+impl<'de> Deserializer<'de> for Repr {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Repr::Unit(_) => visitor.visit_unit(),
+            Repr::Bit(bit) => visitor.visit_bool(*bit),
+            Repr::Key(key) => visitor.visit_string(String::from(&*key)),
+            Repr::Text(text) => visitor.visit_string(String::from(text)),
+            Repr::Int(int) => self::int(int, visitor),
+            Repr::Decimal(decimal) => {
+                let Some(f) = decimal.to_f64() else {
+                    return Err(SerdeError::new(format!("decimal {decimal} out of range")));
+                };
+                visitor.visit_f64(f)
+            },
+            Repr::Byte(byte) => visitor.visit_byte_buf(Vec::from(byte)),
+            Repr::Cell(cell) => visitor.visit_some(cell.value),
+            Repr::Pair(pair) => {
+                let pair = *pair;
+                visitor.visit_seq(SeqDeserializer::new(vec![pair.left, pair.right]))
+            },
+            Repr::Call(call) => {
+                let call = *call;
+                visitor.visit_seq(SeqDeserializer::new(vec![call.func, call.input]))
+            },
+            Repr::List(list) => visitor.visit_seq(SeqDeserializer::new(Vec::from(list))),
+            Repr::Map(map) => visitor.visit_map(MapDeserializer::new(map)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Repr::Unit(_) => visitor.visit_none(),
+            Repr::Cell(cell) => visitor.visit_some(cell.value),
+            repr => visitor.visit_some(repr),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self, name: &'static str, visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        if name == REPR {
+            let mut map = Map::default();
+            map.insert(Key::from_str_unchecked(REPR), Repr::Text(self.to_string().into()));
+            return visitor.visit_map(MapDeserializer::new(map));
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self, _name: &'static str, _variants: &'static [&'static str], visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let (variant, content) = match self {
+            Repr::Key(key) => (String::from(&*key), None),
+            Repr::Text(text) => (String::from(text), None),
+            Repr::Pair(pair) if pair.left.is_key() => {
+                let pair = *pair;
+                let Repr::Key(key) = pair.left else { unreachable!() };
+                (String::from(&*key), Some(pair.right))
+            },
+            Repr::Call(call) if call.func.is_key() => {
+                let call = *call;
+                let Repr::Key(key) = call.func else { unreachable!() };
+                (String::from(&*key), Some(call.input))
+            },
+            Repr::Map(map) if map.len() == 1 => {
+                let (key, value) = map.into_iter().next().unwrap();
+                (String::from(&*key), Some(value))
+            },
+            repr => return Err(SerdeError::new(format!("expected an enum, but got {repr}"))),
+        };
+        visitor.visit_enum(EnumDeserializer { variant, content })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqDeserializer {
+    iter: std::vec::IntoIter<Repr>,
+}
+
+impl SeqDeserializer {
+    fn new(list: Vec<Repr>) -> Self {
+        Self { iter: list.into_iter() }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self, seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        let Some(repr) = self.iter.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(repr).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: <Map<Key, Repr> as IntoIterator>::IntoIter,
+    value: Option<Repr>,
+}
+
+impl MapDeserializer {
+    fn new(map: Map<Key, Repr>) -> Self {
+        Self { iter: map.into_iter(), value: None }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self, seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(KeyDeserializer { key }).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self, seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        let Some(value) = self.value.take() else {
+            return Err(SerdeError::new("value is missing".to_owned()));
+        };
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+// keys of maps with integer keys are generated as keys
+struct KeyDeserializer {
+    key: Key,
+}
+
+impl KeyDeserializer {
+    fn int<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let Ok(i) = self.key.parse::<BigInt>() else {
+            return self.deserialize_any(visitor);
+        };
+        int(Int::from(i), visitor)
+    }
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_string(String::from(&*self.key))
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.int(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self, _name: &'static str, visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self, _name: &'static str, _variants: &'static [&'static str], visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let variant = String::from(&*self.key);
+        visitor.visit_enum(EnumDeserializer { variant, content: None })
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    content: Option<Repr>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = SerdeError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self, seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), SerdeError> {
+        let variant: StringDeserializer<SerdeError> = self.variant.into_deserializer();
+        let variant = seed.deserialize(variant)?;
+        Ok((variant, VariantDeserializer { content: self.content }))
+    }
+}
+
+struct VariantDeserializer {
+    content: Option<Repr>,
+}
+
+impl VariantDeserializer {
+    fn content(self) -> Result<Repr, SerdeError> {
+        let Some(content) = self.content else {
+            return Err(SerdeError::new("expected a variant with content".to_owned()));
+        };
+        Ok(content)
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        match self.content {
+            None | Some(Repr::Unit(_)) => Ok(()),
+            Some(repr) => Err(SerdeError::new(format!("expected a unit variant, but got {repr}"))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self, seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        seed.deserialize(self.content()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self, _len: usize, visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.content()?.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self, _fields: &'static [&'static str], visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.content()?.deserialize_map(visitor)
+    }
+}
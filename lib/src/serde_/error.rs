@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::syntax::ParseError;
+
+pub struct SerdeError {
+    msg: String,
+}
+
+impl SerdeError {
+    pub(super) fn new(msg: String) -> Self {
+        Self { msg }
+    }
+}
+
+impl From<ParseError> for SerdeError {
+    fn from(error: ParseError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+impl serde::ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::new(msg.to_string())
+    }
+}
+
+impl serde::de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::new(msg.to_string())
+    }
+}
+
+impl Debug for SerdeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for SerdeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl Error for SerdeError {}
@@ -0,0 +1,404 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use serde::Serialize;
+use serde::Serializer;
+use serde::ser::SerializeMap;
+use serde::ser::SerializeSeq;
+use serde::ser::SerializeStruct;
+use serde::ser::SerializeStructVariant;
+use serde::ser::SerializeTuple;
+use serde::ser::SerializeTupleStruct;
+use serde::ser::SerializeTupleVariant;
+
+use super::CALL;
+use super::CELL;
+use super::DECIMAL;
+use super::INT;
+use super::KEY;
+use super::PAIR;
+use super::SerdeError;
+use super::key;
+use crate::syntax::repr::Repr;
+use crate::type_::Byte;
+use crate::type_::Call;
+use crate::type_::Cell;
+use crate::type_::Decimal;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Pair;
+use crate::type_::Text;
+use crate::type_::Unit;
+
+pub fn to_repr<T: Serialize + ?Sized>(value: &T) -> Result<Repr, SerdeError> {
+    value.serialize(ReprSerializer)
+}
+
+// generate the value in pretty format
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, SerdeError> {
+    let repr = to_repr(value)?;
+    Ok(format!("{repr:#}"))
+}
+
+pub struct ReprSerializer;
+
+pub struct SeqSerializer {
+    variant: Option<Key>,
+    list: Vec<Repr>,
+}
+
+pub struct MapSerializer {
+    variant: Option<Key>,
+    map: Map<Key, Repr>,
+    key: Option<Key>,
+}
+
+fn variant(variant: &str, content: Repr) -> Result<Repr, SerdeError> {
+    let pair = Pair::new(Repr::Key(key(variant)?), content);
+    Ok(Repr::Pair(Box::new(pair)))
+}
+
+fn text(repr: Repr, name: &str) -> Result<String, SerdeError> {
+    let Repr::Text(text) = repr else {
+        return Err(SerdeError::new(format!("{name}: expected a text, but got {repr}")));
+    };
+    Ok(String::from(text))
+}
+
+fn two(repr: Repr, name: &str) -> Result<(Repr, Repr), SerdeError> {
+    let Repr::List(list) = repr else {
+        return Err(SerdeError::new(format!("{name}: expected a list, but got {repr}")));
+    };
+    let list = Vec::from(list);
+    let Ok([first, second]) = <[Repr; 2]>::try_from(list) else {
+        return Err(SerdeError::new(format!("{name}: expected a list of 2 items")));
+    };
+    Ok((first, second))
+}
+
+fn map_key(repr: Repr) -> Result<Key, SerdeError> {
+    match repr {
+        Repr::Key(k) => Ok(k),
+        Repr::Text(text) => key(&text),
+        Repr::Bit(bit) => key(if *bit { "true" } else { "false" }),
+        Repr::Int(int) => key(&(*int).to_string()),
+        repr => Err(SerdeError::new(format!("expected a key, but got {repr}"))),
+    }
+}
+
+fn decimal(s: &str) -> Result<Repr, SerdeError> {
+    let decimal = BigDecimal::from_str(s).map_err(|e| SerdeError::new(e.to_string()))?;
+    Ok(Repr::Decimal(Decimal::from(decimal)))
+}
+
+impl Serializer for ReprSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Repr, SerdeError> {
+        Ok(Repr::Bit(v.into()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Repr, SerdeError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Repr, SerdeError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Repr, SerdeError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Repr, SerdeError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Repr, SerdeError> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Repr, SerdeError> {
+        self.serialize_u128(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Repr, SerdeError> {
+        self.serialize_u128(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Repr, SerdeError> {
+        self.serialize_u128(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Repr, SerdeError> {
+        self.serialize_u128(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Repr, SerdeError> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    // the shortest text keeps the decimal as written
+    fn serialize_f32(self, v: f32) -> Result<Repr, SerdeError> {
+        decimal(&v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Repr, SerdeError> {
+        decimal(&v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Repr, SerdeError> {
+        Ok(Repr::Text(Text::from(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Repr, SerdeError> {
+        Ok(Repr::Text(Text::from(v.to_owned())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Repr, SerdeError> {
+        Ok(Repr::Byte(Byte::from(v.to_owned())))
+    }
+
+    fn serialize_none(self) -> Result<Repr, SerdeError> {
+        Ok(Repr::Unit(Unit))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Repr, SerdeError> {
+        let repr = value.serialize(self)?;
+        // distinguish from none
+        if repr.is_unit() || repr.is_cell() {
+            return Ok(Repr::Cell(Box::new(Cell::new(repr))));
+        }
+        Ok(repr)
+    }
+
+    fn serialize_unit(self) -> Result<Repr, SerdeError> {
+        Ok(Repr::Unit(Unit))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Repr, SerdeError> {
+        Ok(Repr::Unit(Unit))
+    }
+
+    fn serialize_unit_variant(
+        self, _name: &'static str, _variant_index: u32, variant: &'static str,
+    ) -> Result<Repr, SerdeError> {
+        Ok(Repr::Key(key(variant)?))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self, name: &'static str, value: &T,
+    ) -> Result<Repr, SerdeError> {
+        let repr = value.serialize(self)?;
+        match name {
+            KEY => Ok(Repr::Key(key(&text(repr, name)?)?)),
+            INT => {
+                let int = BigInt::from_str(&text(repr, name)?);
+                let int = int.map_err(|e| SerdeError::new(e.to_string()))?;
+                Ok(Repr::Int(Int::from(int)))
+            },
+            DECIMAL => decimal(&text(repr, name)?),
+            CELL => Ok(Repr::Cell(Box::new(Cell::new(repr)))),
+            PAIR => {
+                let (left, right) = two(repr, name)?;
+                Ok(Repr::Pair(Box::new(Pair::new(left, right))))
+            },
+            CALL => {
+                let (func, input) = two(repr, name)?;
+                Ok(Repr::Call(Box::new(Call::new(func, input))))
+            },
+            _ => Ok(repr),
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T,
+    ) -> Result<Repr, SerdeError> {
+        let content = value.serialize(self)?;
+        self::variant(variant, content)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, SerdeError> {
+        Ok(SeqSerializer { variant: None, list: Vec::with_capacity(len.unwrap_or_default()) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self, _name: &'static str, len: usize,
+    ) -> Result<SeqSerializer, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize,
+    ) -> Result<SeqSerializer, SerdeError> {
+        let variant = Some(key(variant)?);
+        Ok(SeqSerializer { variant, list: Vec::with_capacity(len) })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, SerdeError> {
+        let map = Map::with_capacity(len.unwrap_or_default());
+        Ok(MapSerializer { variant: None, map, key: None })
+    }
+
+    fn serialize_struct(
+        self, _name: &'static str, len: usize,
+    ) -> Result<MapSerializer, SerdeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize,
+    ) -> Result<MapSerializer, SerdeError> {
+        let variant = Some(key(variant)?);
+        Ok(MapSerializer { variant, map: Map::with_capacity(len), key: None })
+    }
+}
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.list.push(value.serialize(ReprSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Repr, SerdeError> {
+        let list = Repr::List(List::from(self.list));
+        match self.variant {
+            Some(variant) => Ok(Repr::Pair(Box::new(Pair::new(Repr::Key(variant), list)))),
+            None => Ok(list),
+        }
+    }
+}
+
+impl SerializeSeq for SeqSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SeqSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SeqSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for SeqSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl MapSerializer {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: Key, value: &T) -> Result<(), SerdeError> {
+        let value = value.serialize(ReprSerializer)?;
+        self.map.insert(key, value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Repr, SerdeError> {
+        let map = Repr::Map(self.map);
+        match self.variant {
+            Some(variant) => Ok(Repr::Pair(Box::new(Pair::new(Repr::Key(variant), map)))),
+            None => Ok(map),
+        }
+    }
+}
+
+impl SerializeMap for MapSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.key = Some(map_key(key.serialize(ReprSerializer)?)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let Some(key) = self.key.take() else {
+            return Err(SerdeError::new("value is serialized before key".to_owned()));
+        };
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for MapSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self, key: &'static str, value: &T,
+    ) -> Result<(), SerdeError> {
+        self.insert(super::key(key)?, value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for MapSerializer {
+    type Ok = Repr;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self, key: &'static str, value: &T,
+    ) -> Result<(), SerdeError> {
+        self.insert(super::key(key)?, value)
+    }
+
+    fn end(self) -> Result<Repr, SerdeError> {
+        self.finish()
+    }
+}
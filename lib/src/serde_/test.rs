@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::from_str;
+use crate::semantics::val::LinkVal;
+use crate::semantics::val::Val;
+use crate::syntax::repr::Repr;
+use crate::to_string;
+use crate::type_::Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    port: u16,
+    ratio: f64,
+    debug: bool,
+    tags: Vec<String>,
+    mode: Mode,
+    modes: Vec<Mode>,
+    limit: Option<u64>,
+    timeout: Option<u64>,
+    nested: Option<Option<u8>>,
+    #[serde(with = "bytes")]
+    data: Vec<u8>,
+    ports: BTreeMap<u16, String>,
+    server: Server,
+    big: i128,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Fast,
+    Slow(u8),
+    Point(i32, i32),
+    Named { level: u8 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    host: String,
+    unit: (),
+}
+
+mod bytes {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+
+    use crate::syntax::repr::Repr;
+
+    pub(super) fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(v)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let Repr::Byte(byte) = Repr::deserialize(d)? else {
+            return Err(serde::de::Error::custom("expected byte"));
+        };
+        Ok(byte.into())
+    }
+}
+
+fn config() -> Config {
+    Config {
+        name: "air".to_owned(),
+        port: 8080,
+        ratio: 0.5,
+        debug: true,
+        tags: vec!["a".to_owned(), "b c".to_owned()],
+        mode: Mode::Fast,
+        modes: vec![Mode::Slow(3), Mode::Point(-1, 2), Mode::Named { level: 7 }],
+        limit: Some(10),
+        timeout: None,
+        nested: Some(None),
+        data: vec![0, 1, 255],
+        ports: BTreeMap::from([(0, "none".to_owned()), (443, "https".to_owned())]),
+        server: Server { host: "localhost".to_owned(), unit: () },
+        big: i128::MIN,
+    }
+}
+
+#[test]
+fn test_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let config = config();
+    let src = to_string(&config)?;
+    let config2: Config = from_str(&src)?;
+    assert_eq!(config, config2, "round trip of {src}");
+    Ok(())
+}
+
+#[test]
+fn test_from_str() -> Result<(), Box<dyn std::error::Error>> {
+    let src = r#"{
+        name : "air",
+        port : 8080,
+        ratio : 0.5,
+        debug : true,
+        tags : ["a", "b c"],
+        mode : Fast,
+        modes : [Slow : 3, Point : [0-1, 2], Named : {level : 7}],
+        limit : 10,
+        timeout : .,
+        nested : .(.),
+        data : byte'X0001ff',
+        ports : {0 : "none", 443 : "https"},
+        server : {host : "localhost", unit : .},
+        big : 0-170141183460469231731687303715884105728,
+    }"#;
+    let config2: Config = from_str(src)?;
+    assert_eq!(config(), config2, "parse {src}");
+    Ok(())
+}
+
+#[test]
+fn test_repr_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let src = "{a : [1, 2.5, 10000000000000000000000000000], b : (x + y) : .(.), \
+        c : byte'B00001010'}";
+    let repr: Repr = src.parse()?;
+    let repr2: Repr = from_str(&to_string(&repr)?)?;
+    assert_eq!(repr, repr2, "repr round trip of {src}");
+    Ok(())
+}
+
+#[test]
+fn test_val_data_only() {
+    let val = Val::Link(LinkVal::new(Val::Unit(Unit), false));
+    assert!(to_string(&val).is_err(), "link should not be serialized");
+}
@@ -0,0 +1,257 @@
+use std::fmt::Formatter;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::Error;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use serde::ser::Error as _;
+
+use super::CALL;
+use super::CELL;
+use super::DECIMAL;
+use super::INT;
+use super::KEY;
+use super::PAIR;
+use super::REPR;
+use crate::semantics::val::Val;
+use crate::syntax::repr::Repr;
+use crate::type_::Bit;
+use crate::type_::Byte;
+use crate::type_::Call;
+use crate::type_::Cell;
+use crate::type_::Decimal;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Pair;
+use crate::type_::Text;
+use crate::type_::Unit;
+
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl Serialize for Bit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(**self)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(KEY, &**self)
+    }
+}
+
+impl Serialize for Text {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl Serialize for Int {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(i) = self.to_i64() {
+            return serializer.serialize_i64(i);
+        }
+        if let Some(u) = self.to_u64() {
+            return serializer.serialize_u64(u);
+        }
+        serializer.serialize_newtype_struct(INT, &(**self).to_string())
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(DECIMAL, &(**self).to_string())
+    }
+}
+
+impl Serialize for Byte {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+impl<T: Serialize> Serialize for Cell<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(CELL, &self.value)
+    }
+}
+
+impl<T: Serialize> Serialize for Pair<T, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(PAIR, &(&self.left, &self.right))
+    }
+}
+
+impl<T: Serialize> Serialize for Call<T, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(CALL, &(&self.func, &self.input))
+    }
+}
+
+impl<T: Serialize> Serialize for List<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&**self)
+    }
+}
+
+impl<T: Serialize> Serialize for Map<Key, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(&**self)
+    }
+}
+
+impl Serialize for Repr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Repr::Unit(unit) => unit.serialize(serializer),
+            Repr::Bit(bit) => bit.serialize(serializer),
+            Repr::Key(key) => key.serialize(serializer),
+            Repr::Text(text) => text.serialize(serializer),
+            Repr::Int(int) => int.serialize(serializer),
+            Repr::Decimal(decimal) => decimal.serialize(serializer),
+            Repr::Byte(byte) => byte.serialize(serializer),
+            Repr::Cell(cell) => cell.serialize(serializer),
+            Repr::Pair(pair) => pair.serialize(serializer),
+            Repr::Call(call) => call.serialize(serializer),
+            Repr::List(list) => list.serialize(serializer),
+            Repr::Map(map) => map.serialize(serializer),
+        }
+    }
+}
+
+// only data can be serialized
+impl Serialize for Val {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Val::Unit(unit) => unit.serialize(serializer),
+            Val::Bit(bit) => bit.serialize(serializer),
+            Val::Key(key) => key.serialize(serializer),
+            Val::Text(text) => (**text).serialize(serializer),
+            Val::Int(int) => (**int).serialize(serializer),
+            Val::Decimal(decimal) => (**decimal).serialize(serializer),
+            Val::Byte(byte) => (**byte).serialize(serializer),
+            Val::Cell(cell) => (**cell).serialize(serializer),
+            Val::Pair(pair) => (**pair).serialize(serializer),
+            Val::Call(call) => (**call).serialize(serializer),
+            Val::List(list) => (**list).serialize(serializer),
+            Val::Map(map) => (**map).serialize(serializer),
+            val => Err(S::Error::custom(format!("expected data, but got {val}"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Repr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(REPR, ReprVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Val {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Repr::deserialize(deserializer).map(Val::from)
+    }
+}
+
+struct ReprVisitor;
+
+impl<'de> Visitor<'de> for ReprVisitor {
+    type Value = Repr;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("air data")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Repr, E> {
+        Ok(Repr::Bit(v.into()))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Repr, E> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Repr, E> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Repr, E> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Repr, E> {
+        Ok(Repr::Int(Int::from(BigInt::from(v))))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Repr, E> {
+        let decimal = BigDecimal::try_from(v).map_err(E::custom)?;
+        Ok(Repr::Decimal(Decimal::from(decimal)))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Repr, E> {
+        Ok(Repr::Text(Text::from(v.to_owned())))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Repr, E> {
+        Ok(Repr::Text(Text::from(v)))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Repr, E> {
+        Ok(Repr::Byte(Byte::from(v.to_owned())))
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Repr, E> {
+        Ok(Repr::Byte(Byte::from(v)))
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Repr, E> {
+        Ok(Repr::Unit(Unit))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Repr, D::Error> {
+        Repr::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Repr, E> {
+        Ok(Repr::Unit(Unit))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self, deserializer: D,
+    ) -> Result<Repr, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Repr, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            list.push(item);
+        }
+        Ok(Repr::List(List::from(list)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Repr, A::Error> {
+        let mut map = Map::with_capacity(access.size_hint().unwrap_or_default());
+        while let Some(key) = access.next_key::<String>()? {
+            if key == REPR && map.is_empty() {
+                let src = access.next_value::<String>()?;
+                return src.parse().map_err(A::Error::custom);
+            }
+            let key = super::key(&key).map_err(A::Error::custom)?;
+            let value = access.next_value()?;
+            map.insert(key, value);
+        }
+        Ok(Repr::Map(map))
+    }
+}
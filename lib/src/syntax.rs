@@ -1,8 +1,18 @@
+pub use self::binary::DecodeError;
+pub use self::binary::decode_binary;
+pub use self::binary::encode_binary;
 pub use self::error::ParseError;
 pub use self::generator::FmtCtx;
 pub use self::generator::FmtRepr;
+pub use self::generator::TrailingSeparator;
+pub use self::json::generate_json;
+pub use self::json::parse_json;
+pub use self::located::LocatedRepr;
+pub use self::located::Span;
+pub use self::located::parse_located;
 pub use self::parser::ParseRepr;
 pub use self::parser::parse;
+pub use self::parser::parse_recover;
 
 _____!();
 
@@ -70,10 +80,18 @@ enum Direction {
 
 pub mod repr;
 
+pub mod located;
+
+pub mod cst;
+
 mod parser;
 
 mod generator;
 
+mod json;
+
+mod binary;
+
 mod error;
 
 #[cfg(test)]
@@ -0,0 +1,360 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use rustc_hash::FxHashMap;
+
+use super::ParseRepr;
+use super::repr::Repr;
+use crate::type_::Bit;
+use crate::type_::Byte;
+use crate::type_::Call;
+use crate::type_::Cell;
+use crate::type_::Decimal;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Pair;
+use crate::type_::Text;
+use crate::type_::Unit;
+
+// layout {
+// magic, version, key table, value
+// key table: count, then each key as a string
+// string: length, then utf-8 bytes
+// value: tag, then payload
+// all counts and lengths are unsigned LEB128 varints
+// } layout
+
+const MAGIC: &[u8] = b"air";
+const VERSION: u8 = 1;
+
+// tags {
+const UNIT: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const KEY: u8 = 3; // index in the key table
+const TEXT: u8 = 4; // string
+const INT: u8 = 5; // length, then little endian two's complement bytes
+const DECIMAL: u8 = 6; // int payload of the digits, then zigzag varint of the scale
+const BYTE: u8 = 7; // length, then bytes
+const CELL: u8 = 8; // value
+const PAIR: u8 = 9; // left value, right value
+const CALL: u8 = 10; // func value, input value
+const LIST: u8 = 11; // count, then values
+const MAP: u8 = 12; // count, then pairs of key index and value
+// } tags
+
+// deeper values are rejected, so malicious input can't overflow the stack
+const MAX_DEPTH: usize = 256;
+
+pub struct DecodeError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl DecodeError {
+    // byte offset of the failing position in the input
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Debug for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DecodeError at byte {}: {}", self.offset, self.msg)
+    }
+}
+
+impl Error for DecodeError {}
+
+pub fn encode_binary(repr: &Repr) -> Vec<u8> {
+    let mut encoder = Encoder::default();
+    encoder.value(repr);
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    varint(&mut bytes, encoder.keys.len() as u64);
+    for key in &encoder.keys {
+        string(&mut bytes, key);
+    }
+    bytes.extend(encoder.body);
+    bytes
+}
+
+pub fn decode_binary<T: ParseRepr>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut decoder = Decoder { bytes, offset: 0, keys: Vec::new(), depth: 0 };
+    if !bytes.starts_with(MAGIC) {
+        return Err(decoder.error("expected magic bytes"));
+    }
+    decoder.offset = MAGIC.len();
+    if decoder.u8()? != VERSION {
+        return Err(decoder.error("unsupported version"));
+    }
+    let count = decoder.len()?;
+    for _ in 0 .. count {
+        let key = decoder.string()?;
+        if !key.chars().all(Key::is_key) {
+            return Err(decoder.error("expected a key"));
+        }
+        decoder.keys.push(Key::from_string_unchecked(key));
+    }
+    let value = decoder.value()?;
+    if decoder.offset != bytes.len() {
+        return Err(decoder.error("expected end of input"));
+    }
+    Ok(value)
+}
+
+#[derive(Default)]
+struct Encoder {
+    keys: Vec<Key>,
+    indices: FxHashMap<Key, u64>,
+    body: Vec<u8>,
+}
+
+impl Encoder {
+    fn key(&mut self, key: &Key) {
+        let index = if let Some(index) = self.indices.get(key) {
+            *index
+        } else {
+            let index = self.keys.len() as u64;
+            self.keys.push(key.clone());
+            self.indices.insert(key.clone(), index);
+            index
+        };
+        varint(&mut self.body, index);
+    }
+
+    fn value(&mut self, repr: &Repr) {
+        match repr {
+            Repr::Unit(_) => self.body.push(UNIT),
+            Repr::Bit(bit) => self.body.push(if **bit { TRUE } else { FALSE }),
+            Repr::Key(key) => {
+                self.body.push(KEY);
+                self.key(key);
+            },
+            Repr::Text(text) => {
+                self.body.push(TEXT);
+                string(&mut self.body, text);
+            },
+            Repr::Int(int) => {
+                self.body.push(INT);
+                int_encode(&mut self.body, int);
+            },
+            Repr::Decimal(decimal) => {
+                self.body.push(DECIMAL);
+                let (digits, scale) = decimal.as_bigint_and_exponent();
+                int_encode(&mut self.body, &digits);
+                varint(&mut self.body, ((scale << 1) ^ (scale >> 63)) as u64);
+            },
+            Repr::Byte(byte) => {
+                self.body.push(BYTE);
+                varint(&mut self.body, byte.len() as u64);
+                self.body.extend_from_slice(byte);
+            },
+            Repr::Cell(cell) => {
+                self.body.push(CELL);
+                self.value(&cell.value);
+            },
+            Repr::Pair(pair) => {
+                self.body.push(PAIR);
+                self.value(&pair.left);
+                self.value(&pair.right);
+            },
+            Repr::Call(call) => {
+                self.body.push(CALL);
+                self.value(&call.func);
+                self.value(&call.input);
+            },
+            Repr::List(list) => {
+                self.body.push(LIST);
+                varint(&mut self.body, list.len() as u64);
+                for item in &**list {
+                    self.value(item);
+                }
+            },
+            Repr::Map(map) => {
+                self.body.push(MAP);
+                varint(&mut self.body, map.len() as u64);
+                for (key, value) in &**map {
+                    self.key(key);
+                    self.value(value);
+                }
+            },
+        }
+    }
+}
+
+fn varint(bytes: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        bytes.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+fn string(bytes: &mut Vec<u8>, s: &str) {
+    varint(bytes, s.len() as u64);
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+fn int_encode(bytes: &mut Vec<u8>, int: &BigInt) {
+    let int = int.to_signed_bytes_le();
+    varint(bytes, int.len() as u64);
+    bytes.extend(int);
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    keys: Vec<Key>,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, msg: &'static str) -> DecodeError {
+        DecodeError { offset: self.offset, msg }
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        let Some(b) = self.bytes.get(self.offset) else {
+            return Err(self.error("unexpected end of input"));
+        };
+        self.offset += 1;
+        Ok(*b)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let Some(bytes) = self.bytes.get(self.offset ..).and_then(|b| b.get(.. len)) else {
+            return Err(self.error("unexpected end of input"));
+        };
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut n = 0u64;
+        let mut shift = 0;
+        loop {
+            let b = self.u8()?;
+            if shift >= 64 || (shift == 63 && b > 1) {
+                return Err(self.error("varint overflow"));
+            }
+            n |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(n);
+            }
+            shift += 7;
+        }
+    }
+
+    // lengths are bounded by the remaining input, so a malicious length can't exhaust memory
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.varint()?;
+        match usize::try_from(len) {
+            Ok(len) if len <= self.bytes.len() - self.offset => Ok(len),
+            _ => Err(self.error("length out of range")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let len = self.len()?;
+        let start = self.offset;
+        let bytes = self.take(len)?;
+        let Ok(s) = std::str::from_utf8(bytes) else {
+            return Err(DecodeError { offset: start, msg: "invalid utf-8" });
+        };
+        Ok(s.to_owned())
+    }
+
+    fn key(&mut self) -> Result<Key, DecodeError> {
+        let index = self.varint()?;
+        let key = usize::try_from(index).ok().and_then(|index| self.keys.get(index));
+        let Some(key) = key else {
+            return Err(self.error("key index out of range"));
+        };
+        Ok(key.clone())
+    }
+
+    fn int(&mut self) -> Result<BigInt, DecodeError> {
+        let len = self.len()?;
+        Ok(BigInt::from_signed_bytes_le(self.take(len)?))
+    }
+
+    fn nested<T: ParseRepr>(&mut self) -> Result<T, DecodeError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.depth += 1;
+        let value = self.value();
+        self.depth -= 1;
+        value
+    }
+
+    fn value<T: ParseRepr>(&mut self) -> Result<T, DecodeError> {
+        let start = self.offset;
+        let value = match self.u8()? {
+            UNIT => T::from(Unit),
+            FALSE => T::from(Bit::false_()),
+            TRUE => T::from(Bit::true_()),
+            KEY => T::from(self.key()?),
+            TEXT => T::from(Text::from(self.string()?)),
+            INT => T::from(Int::from(self.int()?)),
+            DECIMAL => {
+                let digits = self.int()?;
+                let scale = self.varint()?;
+                let scale = ((scale >> 1) as i64) ^ -((scale & 1) as i64);
+                T::from(Decimal::from(BigDecimal::new(digits, scale)))
+            },
+            BYTE => {
+                let len = self.len()?;
+                T::from(Byte::from(self.take(len)?.to_owned()))
+            },
+            CELL => T::from(Cell::new(self.nested()?)),
+            PAIR => {
+                let left = self.nested()?;
+                let right = self.nested()?;
+                T::from(Pair::new(left, right))
+            },
+            CALL => {
+                let func = self.nested()?;
+                let input = self.nested()?;
+                T::from(Call::new(func, input))
+            },
+            LIST => {
+                let len = self.len()?;
+                let mut list = Vec::with_capacity(len);
+                for _ in 0 .. len {
+                    list.push(self.nested()?);
+                }
+                T::from(List::from(list))
+            },
+            MAP => {
+                let len = self.len()?;
+                let mut map = Map::with_capacity(len);
+                for _ in 0 .. len {
+                    let start = self.offset;
+                    let key = self.key()?;
+                    if map.contains_key(&key) {
+                        return Err(DecodeError { offset: start, msg: "duplicate key" });
+                    }
+                    let value = self.nested()?;
+                    map.insert(key, value);
+                }
+                T::from(map)
+            },
+            _ => return Err(DecodeError { offset: start, msg: "unknown tag" }),
+        };
+        Ok(value)
+    }
+}
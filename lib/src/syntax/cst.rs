@@ -0,0 +1,634 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::from_fn;
+use std::str::FromStr;
+
+use const_format::concatcp;
+
+use super::Direction;
+use super::EMPTY;
+use super::LEFT;
+use super::LIST_LEFT;
+use super::LIST_RIGHT;
+use super::MAP_LEFT;
+use super::MAP_RIGHT;
+use super::PAIR;
+use super::ParseError;
+use super::RIGHT;
+use super::SCOPE_LEFT;
+use super::SEPARATOR;
+use super::generator::FmtCtx;
+use super::generator::FmtRepr;
+use super::located::LocatedEntry;
+use super::located::LocatedNode;
+use super::located::LocatedRepr;
+use super::located::Span;
+use super::parser::parse_with_comments;
+use super::repr::Repr;
+use crate::type_::Key;
+
+// lossless syntax tree, keeps the source text together with its located repr and comments
+// edits are applied to the source text, so untouched regions are kept byte by byte
+#[derive(Clone)]
+pub struct Cst {
+    src: String,
+    root: LocatedRepr,
+    comments: Vec<Span>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CstTokenKind {
+    Space,
+    Comment,
+    // delimiters, separators, prefixes and other marks
+    Punct,
+    Key,
+    Atom,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CstToken {
+    pub kind: CstTokenKind,
+    pub span: Span,
+}
+
+impl CstToken {
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, CstTokenKind::Space | CstTokenKind::Comment)
+    }
+}
+
+// a step from a node to one of its children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CstStep {
+    Value,
+    Left,
+    Right,
+    Func,
+    Input,
+    Index(usize),
+    Key(Key),
+}
+
+const LEFT_SCOPE: &str = concatcp!(LEFT, SCOPE_LEFT);
+const RIGHT_SCOPE: &str = concatcp!(RIGHT, SCOPE_LEFT);
+
+struct Found<'a> {
+    node: &'a LocatedRepr,
+    parent: Option<&'a LocatedRepr>,
+    // direction of the scope the node is parsed in
+    direction: Direction,
+    // whether the node should be a single token
+    operand: bool,
+}
+
+impl Cst {
+    pub fn parse(src: String) -> Result<Self, ParseError> {
+        let (root, comments) = parse_with_comments(&src)?;
+        Ok(Self { src, root, comments })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.src
+    }
+
+    pub fn into_source(self) -> String {
+        self.src
+    }
+
+    pub fn root(&self) -> &LocatedRepr {
+        &self.root
+    }
+
+    // spans of outermost comments, in source order
+    pub fn comments(&self) -> &[Span] {
+        &self.comments
+    }
+
+    pub fn text(&self, span: Span) -> &str {
+        &self.src[span.start .. span.end]
+    }
+
+    // all tokens in source order, their texts concatenate to the source
+    pub fn tokens(&self) -> Vec<CstToken> {
+        let mut marks = self
+            .comments
+            .iter()
+            .map(|&span| CstToken { kind: CstTokenKind::Comment, span })
+            .collect::<Vec<_>>();
+        self.collect_tokens(&self.root, &mut marks);
+        marks.sort_by_key(|token| token.span.start);
+
+        let mut tokens = Vec::with_capacity(marks.len() * 2);
+        let mut pos = 0;
+        for mark in marks {
+            self.gap_tokens(pos, mark.span.start, &mut tokens);
+            tokens.push(mark);
+            pos = mark.span.end;
+        }
+        self.gap_tokens(pos, self.src.len(), &mut tokens);
+        tokens
+    }
+
+    fn collect_tokens(&self, node: &LocatedRepr, tokens: &mut Vec<CstToken>) {
+        match &node.node {
+            LocatedNode::Cell(cell) => self.collect_tokens(&cell.value, tokens),
+            LocatedNode::Pair(pair) => {
+                self.collect_tokens(&pair.left, tokens);
+                self.collect_tokens(&pair.right, tokens);
+            },
+            LocatedNode::Call(call) => {
+                self.collect_tokens(&call.func, tokens);
+                self.collect_tokens(&call.input, tokens);
+            },
+            LocatedNode::List(list) => {
+                for item in &**list {
+                    self.collect_tokens(item, tokens);
+                }
+            },
+            LocatedNode::Map(map) => {
+                for entry in map.values() {
+                    tokens.push(CstToken { kind: CstTokenKind::Key, span: entry.key });
+                    self.collect_tokens(&entry.value, tokens);
+                }
+            },
+            _ if node.span.is_empty() => {},
+            _ => {
+                let span = self.atom_span(node);
+                tokens.push(CstToken { kind: CstTokenKind::Atom, span });
+            },
+        }
+    }
+
+    // strip scopes around a node
+    fn atom_span(&self, node: &LocatedRepr) -> Span {
+        let first = first_start(node);
+        let mut span = node.span;
+        loop {
+            // the first child may be scoped by itself
+            if first == Some(span.start) {
+                return span;
+            }
+            let text = self.text(span);
+            let prefix = if text.starts_with(SCOPE_LEFT) {
+                1
+            } else if text.starts_with(LEFT_SCOPE) || text.starts_with(RIGHT_SCOPE) {
+                2
+            } else {
+                return span;
+            };
+            span = self.trim_trivia(Span::new(span.start + prefix, span.end - 1));
+        }
+    }
+
+    fn trim_trivia(&self, mut span: Span) -> Span {
+        loop {
+            let text = self.text(span);
+            let trimmed = text.trim_start();
+            let start = span.start + text.len() - trimmed.len();
+            let trimmed = trimmed.trim_end();
+            let end = start + trimmed.len();
+            let comment_start = self.comments.iter().find(|c| c.start == start);
+            let comment_end = self.comments.iter().find(|c| c.end == end && c.start >= start);
+            let new_span = match (comment_start, comment_end) {
+                (Some(c), _) => Span::new(c.end, end),
+                (None, Some(c)) => Span::new(start, c.start),
+                (None, None) => return Span::new(start, end),
+            };
+            span = new_span;
+        }
+    }
+
+    fn gap_tokens(&self, start: usize, end: usize, tokens: &mut Vec<CstToken>) {
+        let mut space_start = None;
+        for (index, c) in self.src[start .. end].char_indices() {
+            let index = start + index;
+            if c.is_whitespace() {
+                space_start.get_or_insert(index);
+                continue;
+            }
+            if let Some(space_start) = space_start.take() {
+                let span = Span::new(space_start, index);
+                tokens.push(CstToken { kind: CstTokenKind::Space, span });
+            }
+            let span = Span::new(index, index + c.len_utf8());
+            tokens.push(CstToken { kind: CstTokenKind::Punct, span });
+        }
+        if let Some(space_start) = space_start {
+            tokens.push(CstToken { kind: CstTokenKind::Space, span: Span::new(space_start, end) });
+        }
+    }
+
+    pub fn get(&self, path: &[CstStep]) -> Option<&LocatedRepr> {
+        self.find(path).map(|found| found.node)
+    }
+
+    fn find(&self, path: &[CstStep]) -> Option<Found<'_>> {
+        let direction = Direction::default();
+        let mut found = Found { node: &self.root, parent: None, direction, operand: false };
+        for step in path {
+            let direction = self.inner_direction(found.node, found.direction);
+            let raw = self.is_raw(found.node);
+            let (node, operand) = match (&found.node.node, step) {
+                (LocatedNode::Cell(cell), CstStep::Value) => (&cell.value, false),
+                (LocatedNode::Pair(pair), CstStep::Left) => (&pair.left, true),
+                (LocatedNode::Pair(pair), CstStep::Right) => (&pair.right, true),
+                (LocatedNode::Call(call), CstStep::Func) => (&call.func, true),
+                (LocatedNode::Call(call), CstStep::Input) => (&call.input, true),
+                (LocatedNode::List(list), CstStep::Index(index)) => (list.get(*index)?, raw),
+                (LocatedNode::Map(map), CstStep::Key(key)) => (&map.get(key)?.value, raw),
+                _ => return None,
+            };
+            found = Found { node, parent: Some(found.node), direction, operand };
+        }
+        Some(found)
+    }
+
+    // direction of the scope of the children of the node
+    fn inner_direction(&self, node: &LocatedRepr, direction: Direction) -> Direction {
+        let text = self.text(node.span);
+        if first_start(node) == Some(node.span.start) {
+            direction
+        } else if text.starts_with(LEFT_SCOPE) {
+            Direction::Left
+        } else if text.starts_with(RIGHT_SCOPE) {
+            Direction::Right
+        } else {
+            direction
+        }
+    }
+
+    fn is_raw(&self, node: &LocatedRepr) -> bool {
+        self.text(self.atom_span(node)).starts_with(EMPTY)
+    }
+
+    // replace the text of the span and parse again
+    // the cst is unchanged if the new source is invalid
+    pub fn replace(&mut self, span: Span, text: &str) -> Result<(), ParseError> {
+        let mut src = String::with_capacity(self.src.len() - span.len() + text.len());
+        src.push_str(&self.src[.. span.start]);
+        src.push_str(text);
+        src.push_str(&self.src[span.end ..]);
+        *self = Self::parse(src)?;
+        Ok(())
+    }
+
+    // replace the node at the path, returns false if the path doesn't exist or the edit fails
+    pub fn set(&mut self, path: &[CstStep], repr: &Repr) -> bool {
+        let Some(found) = self.find(path) else {
+            return false;
+        };
+        // an implicit node has no text of its own, like the input pair of an infix call
+        if found.parent.is_some_and(|parent| parent.span == found.node.span) {
+            return false;
+        }
+        let span = found.node.span;
+        let text = generate(repr, found.direction, found.operand);
+        // the value of a map entry may be omitted
+        let text = if span.is_empty() { format!(" {PAIR} {text}") } else { text };
+        self.replace(span, &text).is_ok()
+    }
+
+    // insert or update an entry of the map at the path
+    pub fn insert(&mut self, path: &[CstStep], key: Key, repr: &Repr) -> bool {
+        let Some(found) = self.find(path) else {
+            return false;
+        };
+        let LocatedNode::Map(map) = &found.node.node else {
+            return false;
+        };
+        if map.contains_key(&key) {
+            let mut path = path.to_vec();
+            path.push(CstStep::Key(key));
+            return self.set(&path, repr);
+        }
+        let raw = self.is_raw(found.node);
+        let direction = self.inner_direction(found.node, found.direction);
+        let value = generate(repr, direction, raw);
+        let entry = if raw { format!("{key} {value}") } else { format!("{key} {PAIR} {value}") };
+        let last = map.values().map(|entry| entry.key.end.max(entry.value.span.end)).max();
+        let end = self.atom_span(found.node).end - 1;
+        self.append(end, last, raw, &entry)
+    }
+
+    // append an item to the list at the path
+    pub fn push(&mut self, path: &[CstStep], repr: &Repr) -> bool {
+        let Some(found) = self.find(path) else {
+            return false;
+        };
+        let LocatedNode::List(list) = &found.node.node else {
+            return false;
+        };
+        let raw = self.is_raw(found.node);
+        let direction = self.inner_direction(found.node, found.direction);
+        let item = generate(repr, direction, raw);
+        let last = list.last().map(|item| item.span.end);
+        let end = self.atom_span(found.node).end - 1;
+        self.append(end, last, raw, &item)
+    }
+
+    // end is the position of the right delimiter of the container
+    fn append(&mut self, end: usize, last: Option<usize>, raw: bool, text: &str) -> bool {
+        let result = if let Some(last) = last {
+            let separator = if raw { " ".to_owned() } else { format!("{SEPARATOR} ") };
+            self.replace(Span::new(last, last), &format!("{separator}{text}"))
+        } else {
+            self.replace(Span::new(end, end), text)
+        };
+        result.is_ok()
+    }
+
+    // remove the item of a list or the entry of a map at the path
+    pub fn remove(&mut self, path: &[CstStep]) -> bool {
+        let Some((last_step, parent_path)) = path.split_last() else {
+            return false;
+        };
+        let Some(parent) = self.find(parent_path) else {
+            return false;
+        };
+        let raw = self.is_raw(parent.node);
+        let mut spans = match (&parent.node.node, last_step) {
+            (LocatedNode::List(list), CstStep::Index(index)) if *index < list.len() => {
+                list.iter().map(|item| item.span).collect::<Vec<_>>()
+            },
+            (LocatedNode::Map(map), CstStep::Key(key)) if map.contains_key(key) => {
+                map.values().map(entry_span).collect()
+            },
+            _ => return false,
+        };
+        let target = match (&parent.node.node, last_step) {
+            (LocatedNode::Map(map), CstStep::Key(key)) => map[key].key.start,
+            (_, CstStep::Index(index)) => spans[*index].start,
+            _ => unreachable!(),
+        };
+        spans.sort_by_key(|span| span.start);
+        let index = spans.iter().position(|span| span.start == target).unwrap();
+        let item = spans[index];
+        let span = if let Some(next) = spans.get(index + 1) {
+            // comments before the next item belong to it
+            let between = &self.src[item.end .. next.start];
+            let after = between.trim_start();
+            let after = if raw { Some(after) } else { after.strip_prefix(SEPARATOR) };
+            match after {
+                Some(after) => Span::new(item.start, next.start - after.trim_start().len()),
+                None => Span::new(item.start, next.start),
+            }
+        } else if index > 0 {
+            Span::new(spans[index - 1].end, item.end)
+        } else if !raw {
+            let rest = &self.src[item.end ..];
+            let trimmed = rest.trim_start();
+            match trimmed.strip_prefix(SEPARATOR) {
+                Some(after) => Span::new(item.start, self.src.len() - after.len()),
+                None => item,
+            }
+        } else {
+            item
+        };
+        self.replace(span, "").is_ok()
+    }
+
+    // generate the source in pretty format, comments are kept
+    // lists and maps containing comments are broken into lines, with comments kept between items,
+    // other nodes containing comments keep their source text
+    pub fn format(&self, ctx: FmtCtx) -> String {
+        let root = self.root.span;
+        let mut out = String::new();
+        for &comment in self.comments.iter().filter(|c| c.end <= root.start) {
+            out.push_str(self.text(comment));
+            out.push('\n');
+        }
+        out.push_str(&self.format_node(&self.root, Direction::default(), ctx, 0, 0));
+        let mut end = root.end;
+        for &comment in self.comments.iter().filter(|c| c.start >= root.end) {
+            let space = if self.src[end .. comment.start].contains('\n') { '\n' } else { ' ' };
+            out.push(space);
+            out.push_str(self.text(comment));
+            end = comment.end;
+        }
+        out
+    }
+
+    fn format_node(
+        &self, node: &LocatedRepr, direction: Direction, ctx: FmtCtx, depth: usize, column: usize,
+    ) -> String {
+        if !self.comments.iter().any(|c| node.span.contains(*c)) {
+            let repr = Repr::from(node.clone());
+            let ctx = ctx.direction(direction);
+            let margin = " ".repeat(ctx.margin(depth));
+            let text = format!("{:#}", ctx.at(depth, column).display(&repr));
+            // keep the scope of open reprs, unless they are already in a direction scope
+            let scope = (repr.is_pair() || repr.is_call())
+                && self.atom_span(node) != node.span
+                && !text.starts_with(LEFT_SCOPE)
+                && !text.starts_with(RIGHT_SCOPE);
+            let text = if scope {
+                format!("({:#})", ctx.at(depth, column + 1).display(&repr))
+            } else {
+                text
+            };
+            return text.replace('\n', &format!("\n{margin}"));
+        }
+        let inner = self.atom_span(node);
+        if inner != node.span {
+            let child = LocatedRepr { span: inner, node: node.node.clone() };
+            let direction = self.inner_direction(node, direction);
+            return self.format_splice(node.span, &[&child], direction, ctx, depth, column);
+        }
+        let raw = self.is_raw(node);
+        let mut children: Vec<&LocatedRepr> = match &node.node {
+            LocatedNode::Cell(cell) => vec![&cell.value],
+            LocatedNode::Pair(pair) => vec![&pair.left, &pair.right],
+            LocatedNode::Call(call) => match &call.input.node {
+                // infix call
+                LocatedNode::Pair(pair) if call.input.span == node.span => {
+                    vec![&pair.left, &call.func, &pair.right]
+                },
+                _ => vec![&call.func, &call.input],
+            },
+            LocatedNode::List(list) if !raw => {
+                let items = list.iter().map(|item| (item.span, None, item)).collect();
+                let items = Items { span: node.span, left: LIST_LEFT, items, right: LIST_RIGHT };
+                if let Some(text) = self.format_items(&items, direction, ctx, depth) {
+                    return text;
+                }
+                list.iter().collect()
+            },
+            LocatedNode::Map(map) if !raw => {
+                let mut items: Vec<_> = map
+                    .iter()
+                    .map(|(key, entry)| (entry_span(entry), Some(key), &entry.value))
+                    .collect();
+                items.sort_by_key(|(span, ..)| span.start);
+                let items = Items { span: node.span, left: MAP_LEFT, items, right: MAP_RIGHT };
+                if let Some(text) = self.format_items(&items, direction, ctx, depth) {
+                    return text;
+                }
+                map.values().map(|entry| &entry.value).collect()
+            },
+            LocatedNode::List(list) => list.iter().collect(),
+            LocatedNode::Map(map) => map.values().map(|entry| &entry.value).collect(),
+            _ => return self.text(node.span).to_owned(),
+        };
+        children.retain(|child| !child.span.is_empty());
+        children.sort_by_key(|child| child.span.start);
+        self.format_splice(node.span, &children, direction, ctx, depth, column)
+    }
+
+    // format children and keep the text between them, spaces without comments are collapsed
+    fn format_splice(
+        &self, span: Span, children: &[&LocatedRepr], direction: Direction, ctx: FmtCtx,
+        depth: usize, column: usize,
+    ) -> String {
+        let mut out = String::new();
+        let mut pos = span.start;
+        for child in children.iter().copied() {
+            let gap = Span::new(pos, child.span.start);
+            self.format_gap(gap, &mut out);
+            let column = match out.rsplit_once('\n') {
+                Some((_, last)) => last.chars().count(),
+                None => column + out.chars().count(),
+            };
+            out.push_str(&self.format_node(child, direction, ctx, depth, column));
+            pos = child.span.end;
+        }
+        self.format_gap(Span::new(pos, span.end), &mut out);
+        out
+    }
+
+    fn format_gap(&self, gap: Span, out: &mut String) {
+        let text = self.text(gap);
+        if self.comments.iter().any(|c| gap.contains(*c)) {
+            out.push_str(text);
+            return;
+        }
+        let mut space = false;
+        for c in text.chars() {
+            if c.is_whitespace() {
+                space = true;
+                continue;
+            }
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(c);
+        }
+        if space {
+            out.push(' ');
+        }
+    }
+
+    fn format_items(
+        &self, items: &Items<'_>, direction: Direction, ctx: FmtCtx, depth: usize,
+    ) -> Option<String> {
+        // comments between keys and values can't be kept
+        let misplaced = items.items.iter().any(|(span, _, value)| {
+            self.comments.iter().any(|c| span.contains(*c) && !value.span.contains(*c))
+        });
+        if misplaced {
+            return None;
+        }
+        let mut gaps = self
+            .comments
+            .iter()
+            .filter(|c| items.span.contains(**c))
+            .filter(|c| !items.items.iter().any(|(span, ..)| span.contains(**c)))
+            .copied()
+            .peekable();
+        let margin = " ".repeat(ctx.margin(depth + 1));
+        let column = ctx.margin(depth + 1);
+        let mut out = String::new();
+        out.push(items.left);
+        for (index, (span, key, value)) in items.items.iter().enumerate() {
+            while let Some(comment) = gaps.next_if(|c| c.end <= span.start) {
+                out.push('\n');
+                out.push_str(&margin);
+                out.push_str(self.text(comment));
+            }
+            out.push('\n');
+            out.push_str(&margin);
+            match key {
+                None => out.push_str(&self.format_node(value, direction, ctx, depth + 1, column)),
+                Some(key) => {
+                    let key = key.to_string();
+                    out.push_str(&key);
+                    if !value.span.is_empty() {
+                        let column = column + key.chars().count() + PAIR.len() + 2;
+                        let value = self.format_node(value, direction, ctx, depth + 1, column);
+                        out.push(' ');
+                        out.push_str(PAIR);
+                        out.push(' ');
+                        out.push_str(&value);
+                    }
+                },
+            }
+            if ctx.separator(index + 1 == items.items.len(), true) {
+                out.push(SEPARATOR);
+            }
+            // comments on the same line stay after the item
+            let mut end = span.end;
+            while let Some(comment) = gaps.next_if(|c| !self.src[end .. c.start].contains('\n')) {
+                out.push(' ');
+                out.push_str(self.text(comment));
+                end = comment.end;
+            }
+        }
+        for comment in gaps {
+            out.push('\n');
+            out.push_str(&margin);
+            out.push_str(self.text(comment));
+        }
+        out.push('\n');
+        out.push_str(&" ".repeat(ctx.margin(depth)));
+        out.push(items.right);
+        Some(out)
+    }
+}
+
+// items of a list or entries of a map in source order
+struct Items<'a> {
+    span: Span,
+    left: char,
+    items: Vec<(Span, Option<&'a Key>, &'a LocatedRepr)>,
+    right: char,
+}
+
+// the value of an entry may be omitted
+fn entry_span(entry: &LocatedEntry) -> Span {
+    Span::new(entry.key.start, entry.key.end.max(entry.value.span.end))
+}
+
+// start of the first child of an open node
+fn first_start(node: &LocatedRepr) -> Option<usize> {
+    match &node.node {
+        LocatedNode::Pair(pair) => Some(pair.left.span.start),
+        LocatedNode::Call(call) => match &call.input.node {
+            // infix call
+            LocatedNode::Pair(pair) if call.input.span.end >= call.func.span.end => {
+                Some(pair.left.span.start)
+            },
+            _ => Some(call.func.span.start.min(call.input.span.start)),
+        },
+        _ => None,
+    }
+}
+
+fn generate(repr: &Repr, direction: Direction, operand: bool) -> String {
+    let ctx = FmtCtx::default().direction(direction);
+    let text = from_fn(|f| FmtRepr::fmt(repr, ctx, f)).to_string();
+    if operand && (repr.is_pair() || repr.is_call()) { format!("({text})") } else { text }
+}
+
+impl FromStr for Cst {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.to_owned())
+    }
+}
+
+impl Display for Cst {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.src)
+    }
+}
@@ -3,9 +3,73 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
-// todo api better error info
+use winnow::error::ContextError;
+use winnow::error::StrContext;
+
 pub struct ParseError {
-    pub(crate) msg: String,
+    offset: usize,
+    line: usize,
+    column: usize,
+    source_line: String,
+    labels: Vec<&'static str>,
+    expected: Vec<String>,
+}
+
+impl ParseError {
+    pub(crate) fn new(src: &str, offset: usize, error: &ContextError) -> Self {
+        let offset = offset.min(src.len());
+        let line_start = src[.. offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[offset ..].find('\n').map_or(src.len(), |i| offset + i);
+        let line = src[.. line_start].matches('\n').count() + 1;
+        let column = src[line_start .. offset].chars().count() + 1;
+        let source_line = src[line_start .. line_end].trim_end_matches('\r').to_owned();
+        let mut labels = Vec::new();
+        let mut expected = Vec::new();
+        // contexts are pushed while the error bubbles up, so the innermost comes first
+        for context in error.context() {
+            match context {
+                StrContext::Label(label) => labels.push(*label),
+                StrContext::Expected(value) => expected.push(value.to_string()),
+                _ => {},
+            }
+        }
+        Self { offset, line, column, source_line, labels, expected }
+    }
+
+    // byte offset of the failing position in the source
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    // 1-based line number of the failing position
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    // 1-based column (in chars) of the failing position
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    // the whole source line containing the failing position
+    pub fn source_line(&self) -> &str {
+        &self.source_line
+    }
+
+    // the innermost syntax element being parsed
+    pub fn label(&self) -> Option<&'static str> {
+        self.labels.first().copied()
+    }
+
+    // syntax elements being parsed, from innermost to outermost
+    pub fn labels(&self) -> &[&'static str] {
+        &self.labels
+    }
+
+    // descriptions of what was expected at the failing position
+    pub fn expected(&self) -> &[String] {
+        &self.expected
+    }
 }
 
 impl Debug for ParseError {
@@ -16,8 +80,19 @@ impl Debug for ParseError {
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("ParseError\n")?;
-        Display::fmt(&self.msg, f)
+        writeln!(f, "ParseError at line {}, column {}", self.line, self.column)?;
+        let line_num = self.line.to_string();
+        let gutter = " ".repeat(line_num.len());
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_num} | {}", self.source_line)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))?;
+        if let Some(label) = self.label() {
+            write!(f, "\ninvalid {label}")?;
+        }
+        if !self.expected.is_empty() {
+            write!(f, "\nexpected {}", self.expected.join(", "))?;
+        }
+        Ok(())
     }
 }
 
@@ -46,9 +46,154 @@ use crate::type_::Pair;
 use crate::type_::Text;
 use crate::type_::Unit;
 
-#[derive(Default, Copy, Clone)]
+// options of the generator
+// without a max width, pretty format breaks every list and map with more than one item,
+// with a max width, lists, maps and infix calls are only broken into lines when they don't fit
+#[derive(Copy, Clone)]
 pub struct FmtCtx {
     direction: Direction,
+    width: Option<usize>,
+    indent: usize,
+    trailing_separator: TrailingSeparator,
+    sort_keys: bool,
+    // layout state, only tracked when there is a max width
+    depth: usize,
+    column: usize,
+    flat: bool,
+}
+
+// when to generate a separator after the last item of lists and maps
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum TrailingSeparator {
+    #[default]
+    Always,
+    Never,
+    // only when items are broken into lines
+    Vertical,
+}
+
+impl Default for FmtCtx {
+    fn default() -> Self {
+        Self {
+            direction: Direction::default(),
+            width: None,
+            indent: 4,
+            trailing_separator: TrailingSeparator::default(),
+            sort_keys: false,
+            depth: 0,
+            column: 0,
+            flat: false,
+        }
+    }
+}
+
+impl FmtCtx {
+    // max width of lines in pretty format
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // number of spaces of one level of indentation in pretty format
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn trailing_separator(mut self, trailing_separator: TrailingSeparator) -> Self {
+        self.trailing_separator = trailing_separator;
+        self
+    }
+
+    // generate map entries in the order of keys
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    // format flags are interpreted as in FmtRepr::fmt
+    pub fn display(self, repr: &dyn FmtRepr) -> impl Display {
+        from_fn(move |f| repr.fmt(self, f))
+    }
+
+    pub(super) fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    // place the repr after some text at the depth of indentation
+    pub(super) fn at(mut self, depth: usize, column: usize) -> Self {
+        self.depth = depth;
+        self.column = column;
+        self
+    }
+
+    pub(super) fn margin(self, depth: usize) -> usize {
+        depth * self.indent
+    }
+
+    pub(super) fn separator(self, last: bool, vertical: bool) -> bool {
+        match self.trailing_separator {
+            TrailingSeparator::Always => true,
+            TrailingSeparator::Never => !last,
+            TrailingSeparator::Vertical => !last || vertical,
+        }
+    }
+
+    fn vertical(self, f: &Formatter<'_>) -> bool {
+        f.alternate() && !self.flat
+    }
+
+    fn measured(self, f: &Formatter<'_>) -> bool {
+        self.width.is_some() && self.vertical(f)
+    }
+
+    fn nest(mut self) -> Self {
+        self.depth += 1;
+        self.column = self.depth * self.indent;
+        self
+    }
+
+    fn advance(mut self, len: usize) -> Self {
+        self.column += len;
+        self
+    }
+
+    fn after(mut self, s: &str) -> Self {
+        match s.rsplit_once('\n') {
+            Some((_, last)) => self.column = self.depth * self.indent + last.chars().count(),
+            None => self.column += s.chars().count(),
+        }
+        self
+    }
+
+    // the repr in one line if it fits in the rest of the line
+    fn fit(
+        self, f: &Formatter<'_>, repr: impl Fn(FmtCtx, &mut Formatter<'_>) -> std::fmt::Result,
+    ) -> Option<String> {
+        let limit = self.width?.checked_sub(self.column)?;
+        let ctx = FmtCtx { flat: true, ..self };
+        let mut writer = Bounded { s: String::new(), limit };
+        pretty(&mut writer, &from_fn(|f| repr(ctx, f)), f.align()).ok()?;
+        Some(writer.s)
+    }
+}
+
+// fails when the content exceeds the limit or has more than one line
+struct Bounded {
+    s: String,
+    limit: usize,
+}
+
+impl Write for Bounded {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if s.contains('\n') {
+            return Err(std::fmt::Error);
+        }
+        self.limit = self.limit.checked_sub(s.chars().count()).ok_or(std::fmt::Error)?;
+        self.s.push_str(s);
+        Ok(())
+    }
 }
 
 pub trait FmtRepr {
@@ -64,6 +209,14 @@ pub trait FmtRepr {
         false
     }
 
+    fn is_list(&self) -> bool {
+        false
+    }
+
+    fn is_map(&self) -> bool {
+        false
+    }
+
     fn to_pair(&self) -> Pair<&dyn FmtRepr, &dyn FmtRepr> {
         panic!("called `FmtRepr::to_pair()` on non-pair value")
     }
@@ -278,7 +431,7 @@ fn decimal_fmt(decimal: &Decimal, f: &mut Formatter<'_>) -> std::fmt::Result {
     f.write_char('E')?;
     Display::fmt(&decimal.order_of_magnitude(), f)?;
     f.write_char('*')?;
-    let (i, _exp) = decimal.abs().into_bigint_and_scale();
+    let (i, _exp) = BigDecimal::abs(decimal).into_bigint_and_scale();
     let scale = (decimal.digits() - 1) as i64;
     let significand = BigDecimal::from_bigint(i, scale);
     let no_frac = significand.fractional_digit_count() <= 0;
@@ -353,7 +506,7 @@ impl<T: FmtRepr> FmtRepr for Cell<T> {
     fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(UNIT)?;
         f.write_char(SCOPE_LEFT)?;
-        self.value.fmt(ctx, f)?;
+        self.value.fmt(ctx.advance(UNIT.len() + 1), f)?;
         f.write_char(SCOPE_RIGHT)
     }
 }
@@ -381,7 +534,7 @@ impl<T: FmtRepr> FmtRepr for Pair<T, T> {
                 f.write_str(RIGHT)?;
                 f.write_char(SCOPE_LEFT)?;
                 ctx.direction = Direction::Right;
-                pair_fmt_right(self, ctx, f)?;
+                pair_fmt_right(self, ctx.advance(RIGHT.len() + 1), f)?;
                 f.write_char(SCOPE_RIGHT)
             },
             Direction::Right => {
@@ -391,7 +544,7 @@ impl<T: FmtRepr> FmtRepr for Pair<T, T> {
                 f.write_str(LEFT)?;
                 f.write_char(SCOPE_LEFT)?;
                 ctx.direction = Direction::Left;
-                pair_fmt_left(self, ctx, f)?;
+                pair_fmt_left(self, ctx.advance(LEFT.len() + 1), f)?;
                 f.write_char(SCOPE_RIGHT)
             },
         }
@@ -409,21 +562,21 @@ impl<T: FmtRepr> FmtRepr for Pair<T, T> {
 fn pair_fmt_left<T: FmtRepr>(
     pair: &Pair<T, T>, ctx: FmtCtx, f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
-    pair.left.fmt(ctx, f)?;
+    let ctx = operand(ctx, f, |ctx, f| pair.left.fmt(ctx, f))?;
     f.write_char(' ')?;
     f.write_str(PAIR)?;
     f.write_char(' ')?;
-    closure(&pair.right, ctx, f)
+    closure(&pair.right, ctx.advance(PAIR.len() + 2), f)
 }
 
 fn pair_fmt_right<T: FmtRepr>(
     pair: &Pair<T, T>, ctx: FmtCtx, f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
-    closure(&pair.left, ctx, f)?;
+    let ctx = operand(ctx, f, |ctx, f| closure(&pair.left, ctx, f))?;
     f.write_char(' ')?;
     f.write_str(PAIR)?;
     f.write_char(' ')?;
-    pair.right.fmt(ctx, f)
+    pair.right.fmt(ctx.advance(PAIR.len() + 2), f)
 }
 
 impl<T: FmtRepr> Display for Call<T, T> {
@@ -440,11 +593,18 @@ impl<T: FmtRepr> Debug for Call<T, T> {
 
 impl<T: FmtRepr> FmtRepr for Call<T, T> {
     fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if f.alternate() && self.input.is_pair() {
-            call_fmt_infix(&self.func, self.input.to_pair(), ctx, f)
-        } else {
-            call_fmt_default(&self.func, &self.input, ctx, f)
+        if !f.alternate() || !self.input.is_pair() {
+            return call_fmt_default(&self.func, &self.input, ctx, f);
+        }
+        let pair = self.input.to_pair();
+        // lists, maps and calls on the right side break by themselves
+        if !ctx.measured(f) || is_block(pair.right) {
+            return call_fmt_infix(&self.func, pair, ctx, false, f);
         }
+        if let Some(flat) = ctx.fit(f, |ctx, f| FmtRepr::fmt(self, ctx, f)) {
+            return f.write_str(&flat);
+        }
+        call_fmt_infix(&self.func, pair, ctx, true, f)
     }
 
     fn is_call(&self) -> bool {
@@ -453,51 +613,72 @@ impl<T: FmtRepr> FmtRepr for Call<T, T> {
 }
 
 fn call_fmt_infix<T: FmtRepr>(
-    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, mut ctx: FmtCtx, f: &mut Formatter<'_>,
+    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, mut ctx: FmtCtx, broken: bool,
+    f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
     let align = f.align().unwrap_or(Alignment::Center);
     match ctx.direction {
         Direction::Left => {
             if best_left(align, pair.left, pair.right) {
-                return call_fmt_infix_left(func, pair, ctx, f);
+                return call_fmt_infix_left(func, pair, ctx, broken, f);
             }
             f.write_str(RIGHT)?;
             f.write_char(SCOPE_LEFT)?;
             ctx.direction = Direction::Right;
-            call_fmt_infix_right(func, pair, ctx, f)?;
+            call_fmt_infix_right(func, pair, ctx.advance(RIGHT.len() + 1), broken, f)?;
             f.write_char(SCOPE_RIGHT)
         },
         Direction::Right => {
             if best_right(align, pair.left, pair.right) {
-                return call_fmt_infix_right(func, pair, ctx, f);
+                return call_fmt_infix_right(func, pair, ctx, broken, f);
             }
             f.write_str(LEFT)?;
             f.write_char(SCOPE_LEFT)?;
             ctx.direction = Direction::Left;
-            call_fmt_infix_left(func, pair, ctx, f)?;
+            call_fmt_infix_left(func, pair, ctx.advance(LEFT.len() + 1), broken, f)?;
             f.write_char(SCOPE_RIGHT)
         },
     }
 }
 
 fn call_fmt_infix_left<T: FmtRepr>(
-    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, ctx: FmtCtx, f: &mut Formatter<'_>,
+    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, ctx: FmtCtx, broken: bool,
+    f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
-    pair.left.fmt(ctx, f)?;
-    f.write_char(' ')?;
-    closure(func, ctx, f)?;
-    f.write_char(' ')?;
-    closure(pair.right, ctx, f)
+    let ctx = operand(ctx, f, |ctx, f| pair.left.fmt(ctx, f))?;
+    call_fmt_infix_rest(ctx, broken, f, |ctx, f| {
+        let ctx = operand(ctx, f, |ctx, f| closure(func, ctx, f))?;
+        f.write_char(' ')?;
+        closure(pair.right, ctx.advance(1), f)
+    })
 }
 
 fn call_fmt_infix_right<T: FmtRepr>(
-    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, ctx: FmtCtx, f: &mut Formatter<'_>,
+    func: &T, pair: Pair<&dyn FmtRepr, &dyn FmtRepr>, ctx: FmtCtx, broken: bool,
+    f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
-    closure(pair.left, ctx, f)?;
-    f.write_char(' ')?;
-    closure(func, ctx, f)?;
-    f.write_char(' ')?;
-    pair.right.fmt(ctx, f)
+    let ctx = operand(ctx, f, |ctx, f| closure(pair.left, ctx, f))?;
+    call_fmt_infix_rest(ctx, broken, f, |ctx, f| {
+        let ctx = operand(ctx, f, |ctx, f| closure(func, ctx, f))?;
+        f.write_char(' ')?;
+        pair.right.fmt(ctx.advance(1), f)
+    })
+}
+
+// the function and the right operand go to an indented new line when the call is broken
+fn call_fmt_infix_rest(
+    ctx: FmtCtx, broken: bool, f: &mut Formatter<'_>,
+    rest: impl Fn(FmtCtx, &mut Formatter<'_>) -> std::fmt::Result,
+) -> std::fmt::Result {
+    if !broken {
+        f.write_char(' ')?;
+        return rest(ctx.advance(1), f);
+    }
+    let ctx = ctx.nest();
+    let align = f.align();
+    let mut writer = Indent::new(f, ctx.indent, false);
+    writer.write_char('\n')?;
+    pretty(&mut writer, &from_fn(|f| rest(ctx, f)), align)
 }
 
 fn call_fmt_default<T: FmtRepr>(
@@ -512,9 +693,9 @@ fn call_fmt_default<T: FmtRepr>(
 fn call_fmt_left<T: FmtRepr>(
     func: &T, input: &T, ctx: FmtCtx, f: &mut Formatter<'_>,
 ) -> std::fmt::Result {
-    input.fmt(ctx, f)?;
+    let ctx = operand(ctx, f, |ctx, f| input.fmt(ctx, f))?;
     f.write_char(' ')?;
-    closure(func, ctx, f)?;
+    closure(func, ctx.advance(1), f)?;
     f.write_char(' ')?;
     f.write_str(EMPTY)
 }
@@ -524,9 +705,9 @@ fn call_fmt_right<T: FmtRepr>(
 ) -> std::fmt::Result {
     f.write_str(EMPTY)?;
     f.write_char(' ')?;
-    closure(func, ctx, f)?;
+    let ctx = operand(ctx.advance(EMPTY.len() + 1), f, |ctx, f| closure(func, ctx, f))?;
     f.write_char(' ')?;
-    input.fmt(ctx, f)
+    input.fmt(ctx.advance(1), f)
 }
 
 fn best_left(align: Alignment, left: &dyn FmtRepr, right: &dyn FmtRepr) -> bool {
@@ -567,15 +748,34 @@ fn is_open(repr: &dyn FmtRepr) -> bool {
     repr.is_pair() || repr.is_call()
 }
 
+fn is_block(repr: &dyn FmtRepr) -> bool {
+    repr.is_list() || repr.is_map() || repr.is_call()
+}
+
 fn closure(repr: &dyn FmtRepr, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
     if !is_open(repr) {
         return repr.fmt(ctx, f);
     }
     f.write_char(SCOPE_LEFT)?;
-    repr.fmt(ctx, f)?;
+    repr.fmt(ctx.advance(1), f)?;
     f.write_char(SCOPE_RIGHT)
 }
 
+// writes a part of a repr and returns the context at the end of it
+fn operand(
+    ctx: FmtCtx, f: &mut Formatter<'_>,
+    part: impl Fn(FmtCtx, &mut Formatter<'_>) -> std::fmt::Result,
+) -> Result<FmtCtx, std::fmt::Error> {
+    if !ctx.measured(f) {
+        part(ctx, f)?;
+        return Ok(ctx);
+    }
+    let mut s = String::new();
+    pretty(&mut s, &from_fn(|f| part(ctx, f)), f.align())?;
+    f.write_str(&s)?;
+    Ok(ctx.after(&s))
+}
+
 impl<T: FmtRepr> Display for List<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         FmtRepr::fmt(self, FmtCtx::default(), f)
@@ -590,33 +790,12 @@ impl<T: FmtRepr> Debug for List<T> {
 
 impl<T: FmtRepr> FmtRepr for List<T> {
     fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.is_empty() {
-            f.write_char(LIST_LEFT)?;
-            return f.write_char(LIST_RIGHT);
-        }
-
-        if self.len() == 1 {
-            f.write_char(LIST_LEFT)?;
-            self.first().unwrap().fmt(ctx, f)?;
-            return f.write_char(LIST_RIGHT);
-        }
+        let items: Vec<&dyn FmtRepr> = self.iter().map(|repr| repr as &dyn FmtRepr).collect();
+        items_fmt(&items, LIST_LEFT, LIST_RIGHT, ctx, f)
+    }
 
-        f.write_char(LIST_LEFT)?;
-        if f.alternate() {
-            f.write_char('\n')?;
-            for repr in self {
-                let repr = from_fn(|f| repr.fmt(ctx, f));
-                indent(&repr, f)?;
-            }
-        } else {
-            f.write_char(' ')?;
-            for repr in self {
-                repr.fmt(ctx, f)?;
-                f.write_char(SEPARATOR)?;
-                f.write_char(' ')?;
-            }
-        }
-        f.write_char(LIST_RIGHT)
+    fn is_list(&self) -> bool {
+        true
     }
 }
 
@@ -634,59 +813,101 @@ impl<T: FmtRepr> Debug for Map<Key, T> {
 
 impl<T: FmtRepr> FmtRepr for Map<Key, T> {
     fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.is_empty() {
-            f.write_char(MAP_LEFT)?;
-            return f.write_char(MAP_RIGHT);
+        let mut entries: Vec<Entry<T>> =
+            self.iter().map(|(key, value)| Entry { key, value }).collect();
+        if ctx.sort_keys {
+            entries.sort_unstable_by(|a, b| a.key.cmp(b.key));
         }
+        let items: Vec<&dyn FmtRepr> = entries.iter().map(|e| e as &dyn FmtRepr).collect();
+        items_fmt(&items, MAP_LEFT, MAP_RIGHT, ctx, f)
+    }
 
-        if self.len() == 1 {
-            f.write_char(MAP_LEFT)?;
-            let (key, value) = self.iter().next().unwrap();
-            kv_fmt(key.clone(), value, ctx, f)?;
-            return f.write_char(MAP_RIGHT);
-        }
+    fn is_map(&self) -> bool {
+        true
+    }
+}
 
-        f.write_char(MAP_LEFT)?;
-        if f.alternate() {
-            f.write_char('\n')?;
-            for (key, value) in self {
-                let repr = from_fn(|f| kv_fmt(key.clone(), value, ctx, f));
-                indent(&repr, f)?;
-            }
-        } else {
-            f.write_char(' ')?;
-            for (key, value) in self {
-                kv_fmt(key.clone(), value, ctx, f)?;
+struct Entry<'a, T> {
+    key: &'a Key,
+    value: &'a T,
+}
+
+impl<T: FmtRepr> FmtRepr for Entry<'_, T> {
+    fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ctx = operand(ctx, f, |_, f| Display::fmt(self.key, f))?;
+        f.write_char(' ')?;
+        f.write_str(PAIR)?;
+        f.write_char(' ')?;
+        self.value.fmt(ctx.advance(PAIR.len() + 2), f)
+    }
+}
+
+fn items_fmt(
+    items: &[&dyn FmtRepr], left: char, right: char, ctx: FmtCtx, f: &mut Formatter<'_>,
+) -> std::fmt::Result {
+    if items.is_empty() {
+        f.write_char(left)?;
+        return f.write_char(right);
+    }
+
+    if ctx.measured(f) {
+        if let Some(flat) = ctx.fit(f, |ctx, f| items_fmt(items, left, right, ctx, f)) {
+            return f.write_str(&flat);
+        }
+    } else if items.len() == 1 {
+        f.write_char(left)?;
+        items[0].fmt(ctx.advance(1), f)?;
+        return f.write_char(right);
+    }
+
+    f.write_char(left)?;
+    if ctx.vertical(f) {
+        f.write_char('\n')?;
+        let ctx = ctx.nest();
+        for (i, item) in items.iter().enumerate() {
+            let separator = ctx.separator(i + 1 == items.len(), true);
+            indent(&ctx.display(*item), separator, ctx.indent, f)?;
+        }
+    } else {
+        f.write_char(' ')?;
+        for (i, item) in items.iter().enumerate() {
+            FmtRepr::fmt(*item, ctx, f)?;
+            if ctx.separator(i + 1 == items.len(), false) {
                 f.write_char(SEPARATOR)?;
-                f.write_char(' ')?;
             }
+            f.write_char(' ')?;
         }
-        f.write_char(MAP_RIGHT)
     }
+    f.write_char(right)
 }
 
-fn kv_fmt<T: FmtRepr>(key: Key, value: &T, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
-    Display::fmt(&key, f)?;
-    f.write_char(' ')?;
-    f.write_str(PAIR)?;
-    f.write_char(' ')?;
-    value.fmt(ctx, f)
+fn indent(
+    repr: &dyn Display, separator: bool, width: usize, f: &mut Formatter<'_>,
+) -> std::fmt::Result {
+    let align = f.align();
+    let mut writer = Indent::new(f, width, true);
+    pretty(&mut writer, repr, align)?;
+    if separator {
+        writer.write_char(SEPARATOR)?;
+    }
+    writer.write_char('\n')
 }
 
 // TODO impl options lost
-fn indent(repr: &dyn Display, f: &mut Formatter<'_>) -> std::fmt::Result {
-    let align = f.align();
-    let mut writer = Indent::new(f);
+fn pretty(
+    writer: &mut impl Write, repr: &dyn Display, align: Option<Alignment>,
+) -> std::fmt::Result {
     match align {
-        Some(Alignment::Left) => writeln!(writer, "{repr:<#}{SEPARATOR}"),
-        Some(Alignment::Center) => writeln!(writer, "{repr:^#}{SEPARATOR}"),
-        Some(Alignment::Right) => writeln!(writer, "{repr:>#}{SEPARATOR}"),
-        None => writeln!(writer, "{repr:#}{SEPARATOR}"),
+        Some(Alignment::Left) => write!(writer, "{repr:<#}"),
+        Some(Alignment::Center) => write!(writer, "{repr:^#}"),
+        Some(Alignment::Right) => write!(writer, "{repr:>#}"),
+        None => write!(writer, "{repr:#}"),
     }
 }
 
 struct Indent<'a, 'b> {
     fmt: &'a mut Formatter<'b>,
+    width: usize,
     on_newline: bool,
 }
 
@@ -694,7 +915,7 @@ impl<'a, 'b> Write for Indent<'a, 'b> {
     fn write_str(&mut self, s: &str) -> std::fmt::Result {
         for s in s.split_inclusive('\n') {
             if self.on_newline {
-                self.fmt.write_str("    ")?;
+                self.pad()?;
             }
             self.on_newline = s.ends_with('\n');
             self.fmt.write_str(s)?;
@@ -704,7 +925,7 @@ impl<'a, 'b> Write for Indent<'a, 'b> {
 
     fn write_char(&mut self, c: char) -> std::fmt::Result {
         if self.on_newline {
-            self.fmt.write_str("    ")?;
+            self.pad()?;
         }
         self.on_newline = c == '\n';
         self.fmt.write_char(c)
@@ -712,7 +933,14 @@ impl<'a, 'b> Write for Indent<'a, 'b> {
 }
 
 impl<'a, 'b> Indent<'a, 'b> {
-    fn new(fmt: &'a mut Formatter<'b>) -> Self {
-        Indent { fmt, on_newline: true }
+    fn new(fmt: &'a mut Formatter<'b>, width: usize, on_newline: bool) -> Self {
+        Indent { fmt, width, on_newline }
+    }
+
+    fn pad(&mut self) -> std::fmt::Result {
+        for _ in 0 .. self.width {
+            self.fmt.write_char(' ')?;
+        }
+        Ok(())
     }
 }
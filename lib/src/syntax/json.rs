@@ -0,0 +1,391 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use winnow::ModalResult;
+use winnow::Parser;
+use winnow::combinator::alt;
+use winnow::combinator::cut_err;
+use winnow::combinator::delimited;
+use winnow::combinator::fail;
+use winnow::combinator::opt;
+use winnow::combinator::peek;
+use winnow::combinator::preceded;
+use winnow::combinator::repeat;
+use winnow::combinator::separated;
+use winnow::error::ContextError;
+use winnow::error::ErrMode;
+use winnow::stream::Stream;
+use winnow::token::any;
+use winnow::token::one_of;
+use winnow::token::take_while;
+
+use super::ParseError;
+use super::ParseRepr;
+use super::parser::cut_expect_desc;
+use super::parser::expect_char;
+use super::parser::expect_desc;
+use super::parser::label;
+use super::repr::Repr;
+use crate::type_::Bit;
+use crate::type_::Byte;
+use crate::type_::Call;
+use crate::type_::Cell;
+use crate::type_::Decimal;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Pair;
+use crate::type_::Text;
+use crate::type_::Unit;
+use crate::utils::conversion::hex_str_to_vec_u8;
+use crate::utils::conversion::u8_array_to_hex_string_mut;
+
+// encoding {
+// unit <-> null
+// bit <-> true / false
+// text <-> string
+// int <-> number without fraction and exponent, of any size
+// decimal <-> number with fraction or exponent, of any precision
+// list <-> array
+// map <-> object, keys must be valid keys
+// other data are encoded as an object with a single tagged member
+const TAG_KEY: &str = "$key"; // {"$key": "name"}
+const TAG_BYTE: &str = "$byte"; // {"$byte": "00ff"} in hex
+const TAG_CELL: &str = "$cell"; // {"$cell": value}
+const TAG_PAIR: &str = "$pair"; // {"$pair": [left, right]}
+const TAG_CALL: &str = "$call"; // {"$call": [func, input]}
+// a map with a tag as its key is escaped as {"$map": {...}}
+const TAG_MAP: &str = "$map";
+// } encoding
+
+const TAGS: [&str; 6] = [TAG_KEY, TAG_BYTE, TAG_CELL, TAG_PAIR, TAG_CALL, TAG_MAP];
+
+type E = ErrMode<ContextError>;
+
+pub fn parse_json<T: ParseRepr>(src: &str) -> Result<T, ParseError> {
+    delimited(ws, value::<T>, ws)
+        .parse(src)
+        .map_err(|e| ParseError::new(src, e.offset(), e.inner()))
+}
+
+pub fn generate_json(repr: &Repr) -> String {
+    let mut s = String::new();
+    generate(repr, &mut s);
+    s
+}
+
+fn ws(i: &mut &str) -> ModalResult<()> {
+    take_while(0 .., |c| matches!(c, ' ' | '\t' | '\n' | '\r')).void().parse_next(i)
+}
+
+fn token<'a>(c: char) -> impl Parser<&'a str, char, E> {
+    delimited(ws, c.context(expect_char(c)), ws)
+}
+
+fn value<T: ParseRepr>(i: &mut &str) -> ModalResult<T> {
+    match peek(any).parse_next(i)? {
+        '{' => object(i),
+        '[' => array(i).map(T::from),
+        '"' => string(i).map(|s| T::from(Text::from(s))),
+        't' => "true".value(true).map(|b| T::from(Bit::from(b))).parse_next(i),
+        'f' => "false".value(false).map(|b| T::from(Bit::from(b))).parse_next(i),
+        'n' => "null".map(|_| T::from(Unit)).parse_next(i),
+        '-' | '0' ..= '9' => number(i),
+        _ => fail.context(expect_desc("value")).parse_next(i),
+    }
+}
+
+fn array<T: ParseRepr>(i: &mut &str) -> ModalResult<List<T>> {
+    let items = separated(0 .., preceded(ws, value::<T>), token(','));
+    let f = delimited('[', cut_err(items), cut_err(token(']')));
+    f.map(|list: Vec<T>| List::from(list)).context(label("array")).parse_next(i)
+}
+
+fn two<T: ParseRepr>(i: &mut &str) -> ModalResult<(T, T)> {
+    let f = (preceded(ws, value::<T>), token(','), preceded(ws, value::<T>));
+    let f = delimited(token('['), f, token(']'));
+    f.map(|(first, _, second)| (first, second)).parse_next(i)
+}
+
+fn object<T: ParseRepr>(i: &mut &str) -> ModalResult<T> {
+    let f = move |i: &mut _| {
+        token('{').parse_next(i)?;
+        if opt('}').parse_next(i)?.is_some() {
+            return Ok(T::from(Map::default()));
+        }
+        let first = cut_err(string).parse_next(i)?;
+        cut_err(token(':')).parse_next(i)?;
+        // only an object with a single member is tagged
+        if TAGS.contains(&&*first) {
+            let start = i.checkpoint();
+            let single = |i: &mut &str| opt(peek(token('}'))).parse_next(i).map(|o| o.is_some());
+            match tagged::<T>(&first, i) {
+                Ok(tagged) if single(i)? => {
+                    token('}').parse_next(i)?;
+                    return Ok(tagged);
+                },
+                Ok(_) => i.reset(&start),
+                Err(e) => {
+                    i.reset(&start);
+                    cut_err(value::<T>).parse_next(i)?;
+                    if single(i)? {
+                        return Err(e.cut());
+                    }
+                    i.reset(&start);
+                },
+            }
+        }
+        let first = map_key(&first)?;
+        let value = cut_err(value::<T>).parse_next(i)?;
+        let map = members(Some((first, value)), i)?;
+        Ok(T::from(map))
+    };
+    f.context(label("object")).parse_next(i)
+}
+
+fn tagged<T: ParseRepr>(tag: &str, i: &mut &str) -> ModalResult<T> {
+    match tag {
+        TAG_KEY => {
+            let s = string(i)?;
+            Ok(T::from(map_key(&s)?))
+        },
+        TAG_BYTE => {
+            let s = string(i)?;
+            let Ok(byte) = hex_str_to_vec_u8(&s) else {
+                return Err(cut_expect_desc("hex digits"));
+            };
+            Ok(T::from(Byte::from(byte)))
+        },
+        TAG_CELL => value::<T>.map(|v| T::from(Cell::new(v))).parse_next(i),
+        TAG_PAIR => two::<T>.map(|(l, r)| T::from(Pair::new(l, r))).parse_next(i),
+        TAG_CALL => two::<T>.map(|(f, input)| T::from(Call::new(f, input))).parse_next(i),
+        TAG_MAP => {
+            token('{').parse_next(i)?;
+            if opt('}').parse_next(i)?.is_some() {
+                return Ok(T::from(Map::default()));
+            }
+            Ok(T::from(members::<T>(None, i)?))
+        },
+        _ => unreachable!("invalid tag {tag}"),
+    }
+}
+
+// parse the rest members and the right delimiter of an object
+fn members<T: ParseRepr>(first: Option<(Key, T)>, i: &mut &str) -> ModalResult<Map<Key, T>> {
+    let mut map = Map::default();
+    let need_separator = first.is_some();
+    if let Some((key, value)) = first {
+        map.insert(key, value);
+    }
+    let member = |i: &mut &str| {
+        let key = string(i)?;
+        let key = map_key(&key)?;
+        token(':').parse_next(i)?;
+        let value = value::<T>(i)?;
+        Ok((key, value))
+    };
+    let mut member = cut_err(member);
+    if !need_separator {
+        let (key, value) = member.parse_next(i)?;
+        map.insert(key, value);
+    }
+    let rest: Vec<(Key, T)> = repeat(0 .., preceded(token(','), member)).parse_next(i)?;
+    map.extend(rest);
+    cut_err(token('}')).parse_next(i)?;
+    Ok(map)
+}
+
+fn map_key(s: &str) -> ModalResult<Key> {
+    if !s.chars().all(Key::is_key) {
+        return Err(cut_expect_desc("key"));
+    }
+    Ok(Key::from_str_unchecked(s))
+}
+
+fn number<T: ParseRepr>(i: &mut &str) -> ModalResult<T> {
+    let f = move |i: &mut &str| {
+        let mut s = String::new();
+        if opt('-').parse_next(i)?.is_some() {
+            s.push('-');
+        }
+        let int = alt(("0", digits)).parse_next(i)?;
+        s.push_str(int);
+        if int == "0" && opt(peek(digits)).parse_next(i)?.is_some() {
+            return Err(cut_expect_desc("no leading zeros"));
+        }
+        let fraction = opt(preceded('.', cut_err(digits))).parse_next(i)?;
+        if let Some(fraction) = fraction {
+            s.push('.');
+            s.push_str(fraction);
+        }
+        let exponent = opt(one_of(['e', 'E'])).parse_next(i)?;
+        if exponent.is_some() {
+            s.push('e');
+            if let Some(sign) = opt(one_of(['+', '-'])).parse_next(i)? {
+                s.push(sign);
+            }
+            s.push_str(cut_err(digits).parse_next(i)?);
+        }
+        if fraction.is_some() || exponent.is_some() {
+            let Ok(d) = BigDecimal::from_str(&s) else {
+                return Err(cut_expect_desc("decimal"));
+            };
+            Ok(T::from(Decimal::from(d)))
+        } else {
+            let Ok(int) = BigInt::from_str(&s) else {
+                return Err(cut_expect_desc("integer"));
+            };
+            Ok(T::from(Int::from(int)))
+        }
+    };
+    f.context(label("number")).parse_next(i)
+}
+
+fn digits<'a>(i: &mut &'a str) -> ModalResult<&'a str> {
+    take_while(1 .., |c: char| c.is_ascii_digit()).parse_next(i)
+}
+
+fn string(i: &mut &str) -> ModalResult<String> {
+    let f = move |i: &mut &str| {
+        '"'.context(expect_char('"')).parse_next(i)?;
+        let mut s = String::new();
+        loop {
+            let literal = take_while(0 .., |c| c != '"' && c != '\\' && c >= ' ');
+            s.push_str(cut_err(literal).parse_next(i)?);
+            match cut_err(any).parse_next(i)? {
+                '"' => return Ok(s),
+                '\\' => s.push(cut_err(escape).parse_next(i)?),
+                _ => return Err(cut_expect_desc("escaped control character")),
+            }
+        }
+    };
+    f.context(label("string")).parse_next(i)
+}
+
+fn escape(i: &mut &str) -> ModalResult<char> {
+    let c = match any.parse_next(i)? {
+        '"' => '"',
+        '\\' => '\\',
+        '/' => '/',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'u' => {
+            let high = hex4(i)?;
+            if !(0xd800 .. 0xdc00).contains(&high) {
+                return char::from_u32(high).ok_or_else(|| cut_expect_desc("unicode"));
+            }
+            let low = preceded("\\u", hex4).parse_next(i)?;
+            if !(0xdc00 .. 0xe000).contains(&low) {
+                return Err(cut_expect_desc("low surrogate"));
+            }
+            let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+            return char::from_u32(c).ok_or_else(|| cut_expect_desc("unicode"));
+        },
+        _ => return fail.context(expect_desc("escape")).parse_next(i),
+    };
+    Ok(c)
+}
+
+fn hex4(i: &mut &str) -> ModalResult<u32> {
+    let digits = take_while(4, |c: char| c.is_ascii_hexdigit());
+    let f = digits.try_map(|s| u32::from_str_radix(s, 16));
+    f.context(expect_desc("4 hex digits")).parse_next(i)
+}
+
+fn generate(repr: &Repr, s: &mut String) {
+    match repr {
+        Repr::Unit(_) => s.push_str("null"),
+        Repr::Bit(bit) => s.push_str(if **bit { "true" } else { "false" }),
+        Repr::Key(key) => tagged_generate(s, TAG_KEY, |s| string_generate(key, s)),
+        Repr::Text(text) => string_generate(text, s),
+        Repr::Int(int) => write!(s, "{}", &**int).unwrap(),
+        Repr::Decimal(decimal) => {
+            let start = s.len();
+            write!(s, "{}", &**decimal).unwrap();
+            // keep it a decimal when parsed back
+            if !s[start ..].contains(['.', 'e', 'E']) {
+                s.push_str("e0");
+            }
+        },
+        Repr::Byte(byte) => tagged_generate(s, TAG_BYTE, |s| {
+            s.push('"');
+            u8_array_to_hex_string_mut(byte, s);
+            s.push('"');
+        }),
+        Repr::Cell(cell) => tagged_generate(s, TAG_CELL, |s| generate(&cell.value, s)),
+        Repr::Pair(pair) => {
+            tagged_generate(s, TAG_PAIR, |s| two_generate(&pair.left, &pair.right, s));
+        },
+        Repr::Call(call) => {
+            tagged_generate(s, TAG_CALL, |s| two_generate(&call.func, &call.input, s));
+        },
+        Repr::List(list) => {
+            s.push('[');
+            for (index, item) in list.iter().enumerate() {
+                if index != 0 {
+                    s.push(',');
+                }
+                generate(item, s);
+            }
+            s.push(']');
+        },
+        Repr::Map(map) => {
+            if map.keys().any(|key| TAGS.contains(&&**key)) {
+                tagged_generate(s, TAG_MAP, |s| members_generate(map, s));
+            } else {
+                members_generate(map, s);
+            }
+        },
+    }
+}
+
+fn tagged_generate(s: &mut String, tag: &str, f: impl FnOnce(&mut String)) {
+    s.push('{');
+    string_generate(tag, s);
+    s.push(':');
+    f(s);
+    s.push('}');
+}
+
+fn two_generate(first: &Repr, second: &Repr, s: &mut String) {
+    s.push('[');
+    generate(first, s);
+    s.push(',');
+    generate(second, s);
+    s.push(']');
+}
+
+fn members_generate(map: &Map<Key, Repr>, s: &mut String) {
+    s.push('{');
+    for (index, (key, value)) in map.iter().enumerate() {
+        if index != 0 {
+            s.push(',');
+        }
+        string_generate(key, s);
+        s.push(':');
+        generate(value, s);
+    }
+    s.push('}');
+}
+
+fn string_generate(str: &str, s: &mut String) {
+    s.push('"');
+    for c in str.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if c < ' ' => write!(s, "\\u{:04x}", c as u32).unwrap(),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
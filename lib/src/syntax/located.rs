@@ -0,0 +1,271 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use derive_more::From;
+use derive_more::IsVariant;
+
+use super::ParseError;
+use super::generator::FmtCtx;
+use super::generator::FmtRepr;
+use super::parser::ParseRepr;
+use super::parser::parse;
+use super::repr::Repr;
+use crate::type_::Bit;
+use crate::type_::Byte;
+use crate::type_::Call;
+use crate::type_::Cell;
+use crate::type_::Decimal;
+use crate::type_::Int;
+use crate::type_::Key;
+use crate::type_::List;
+use crate::type_::Map;
+use crate::type_::Pair;
+use crate::type_::Text;
+use crate::type_::Unit;
+
+// byte range of a syntax element in the source
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, other: Span) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+}
+
+// a repr annotated with the source span of every node
+#[derive(PartialEq, Eq, Clone)]
+pub struct LocatedRepr {
+    pub span: Span,
+    pub node: LocatedNode,
+}
+
+#[derive(PartialEq, Eq, Clone, From, IsVariant)]
+pub enum LocatedNode {
+    Unit(Unit),
+    Bit(Bit),
+
+    Key(Key),
+
+    Text(Text),
+    Int(Int),
+    Decimal(Decimal),
+    Byte(Byte),
+
+    Cell(Box<Cell<LocatedRepr>>),
+    Pair(Box<Pair<LocatedRepr, LocatedRepr>>),
+    Call(Box<Call<LocatedRepr, LocatedRepr>>),
+
+    List(List<LocatedRepr>),
+    Map(Map<Key, LocatedEntry>),
+}
+
+// a map value together with the source span of its key
+#[derive(PartialEq, Eq, Clone)]
+pub struct LocatedEntry {
+    pub key: Span,
+    pub value: LocatedRepr,
+}
+
+pub fn parse_located(src: &str) -> Result<LocatedRepr, ParseError> {
+    parse(src)
+}
+
+macro_rules! impl_from_for_located_repr {
+    ($($t:ty)*) => {
+        $(impl From<$t> for LocatedRepr {
+            fn from(t: $t) -> Self {
+                LocatedRepr { span: Span::default(), node: LocatedNode::from(t) }
+            }
+        })*
+    };
+}
+
+impl_from_for_located_repr!(Unit Bit Key Text Int Decimal Byte List<LocatedRepr>);
+
+impl From<Cell<LocatedRepr>> for LocatedRepr {
+    fn from(cell: Cell<LocatedRepr>) -> Self {
+        LocatedRepr { span: Span::default(), node: LocatedNode::Cell(Box::new(cell)) }
+    }
+}
+
+impl From<Pair<LocatedRepr, LocatedRepr>> for LocatedRepr {
+    fn from(pair: Pair<LocatedRepr, LocatedRepr>) -> Self {
+        LocatedRepr { span: Span::default(), node: LocatedNode::Pair(Box::new(pair)) }
+    }
+}
+
+impl From<Call<LocatedRepr, LocatedRepr>> for LocatedRepr {
+    fn from(call: Call<LocatedRepr, LocatedRepr>) -> Self {
+        LocatedRepr { span: Span::default(), node: LocatedNode::Call(Box::new(call)) }
+    }
+}
+
+impl From<Map<Key, LocatedRepr>> for LocatedRepr {
+    fn from(map: Map<Key, LocatedRepr>) -> Self {
+        let map = map
+            .into_iter()
+            .map(|(k, value)| (k, LocatedEntry { key: Span::default(), value }))
+            .collect();
+        LocatedRepr { span: Span::default(), node: LocatedNode::Map(map) }
+    }
+}
+
+impl ParseRepr for LocatedRepr {
+    const LOCATE: bool = true;
+
+    fn locate(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    fn locate_keys(mut self, keys: Map<Key, Span>) -> Self {
+        if let LocatedNode::Map(map) = &mut self.node {
+            for (key, span) in keys {
+                if let Some(entry) = map.get_mut(&key) {
+                    entry.key = span;
+                }
+            }
+        }
+        self
+    }
+}
+
+impl From<LocatedRepr> for Repr {
+    fn from(located: LocatedRepr) -> Self {
+        match located.node {
+            LocatedNode::Unit(unit) => Repr::Unit(unit),
+            LocatedNode::Bit(bit) => Repr::Bit(bit),
+            LocatedNode::Key(key) => Repr::Key(key),
+            LocatedNode::Text(text) => Repr::Text(text),
+            LocatedNode::Int(int) => Repr::Int(int),
+            LocatedNode::Decimal(decimal) => Repr::Decimal(decimal),
+            LocatedNode::Byte(byte) => Repr::Byte(byte),
+            LocatedNode::Cell(cell) => Repr::from(Cell::new(Repr::from(cell.value))),
+            LocatedNode::Pair(pair) => {
+                let pair = Pair::new(Repr::from(pair.left), Repr::from(pair.right));
+                Repr::from(pair)
+            },
+            LocatedNode::Call(call) => {
+                let call = Call::new(Repr::from(call.func), Repr::from(call.input));
+                Repr::from(call)
+            },
+            LocatedNode::List(list) => {
+                let list: Vec<Repr> = list.into_iter().map(Repr::from).collect();
+                Repr::List(list.into())
+            },
+            LocatedNode::Map(map) => {
+                let map = map.into_iter().map(|(k, entry)| (k, Repr::from(entry.value)));
+                Repr::Map(map.collect())
+            },
+        }
+    }
+}
+
+impl FromStr for LocatedRepr {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl Display for LocatedRepr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        FmtRepr::fmt(self, FmtCtx::default(), f)
+    }
+}
+
+impl Debug for LocatedRepr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        FmtRepr::fmt(self, FmtCtx::default(), f)
+    }
+}
+
+impl FmtRepr for LocatedRepr {
+    fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.node {
+            LocatedNode::Unit(unit) => <Unit as Display>::fmt(unit, f),
+            LocatedNode::Bit(bit) => <Bit as Display>::fmt(bit, f),
+            LocatedNode::Key(key) => <Key as Display>::fmt(key, f),
+            LocatedNode::Text(text) => <Text as Display>::fmt(text, f),
+            LocatedNode::Int(int) => <Int as Display>::fmt(int, f),
+            LocatedNode::Decimal(decimal) => <Decimal as Display>::fmt(decimal, f),
+            LocatedNode::Byte(byte) => <Byte as Display>::fmt(byte, f),
+            LocatedNode::Cell(cell) => <Cell<LocatedRepr> as FmtRepr>::fmt(cell, ctx, f),
+            LocatedNode::Pair(pair) => {
+                <Pair<LocatedRepr, LocatedRepr> as FmtRepr>::fmt(pair, ctx, f)
+            },
+            LocatedNode::Call(call) => {
+                <Call<LocatedRepr, LocatedRepr> as FmtRepr>::fmt(call, ctx, f)
+            },
+            LocatedNode::List(list) => <List<LocatedRepr> as FmtRepr>::fmt(list, ctx, f),
+            LocatedNode::Map(map) => <Map<Key, LocatedEntry> as FmtRepr>::fmt(map, ctx, f),
+        }
+    }
+
+    fn is_call(&self) -> bool {
+        self.node.is_call()
+    }
+
+    fn is_pair(&self) -> bool {
+        self.node.is_pair()
+    }
+
+    fn is_list(&self) -> bool {
+        self.node.is_list()
+    }
+
+    fn is_map(&self) -> bool {
+        self.node.is_map()
+    }
+
+    fn to_pair(&self) -> Pair<&dyn FmtRepr, &dyn FmtRepr> {
+        let LocatedNode::Pair(pair) = &self.node else {
+            panic!("called `FmtRepr::to_pair()` on non-pair value")
+        };
+        Pair::new(&pair.left, &pair.right)
+    }
+}
+
+impl FmtRepr for LocatedEntry {
+    fn fmt(&self, ctx: FmtCtx, f: &mut Formatter<'_>) -> std::fmt::Result {
+        FmtRepr::fmt(&self.value, ctx, f)
+    }
+
+    fn is_call(&self) -> bool {
+        self.value.is_call()
+    }
+
+    fn is_pair(&self) -> bool {
+        self.value.is_pair()
+    }
+
+    fn is_list(&self) -> bool {
+        self.value.is_list()
+    }
+
+    fn is_map(&self) -> bool {
+        self.value.is_map()
+    }
+
+    fn to_pair(&self) -> Pair<&dyn FmtRepr, &dyn FmtRepr> {
+        self.value.to_pair()
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::hash::Hash;
 use std::str::FromStr;
 
@@ -21,7 +22,6 @@ use winnow::combinator::opt;
 use winnow::combinator::peek;
 use winnow::combinator::preceded;
 use winnow::combinator::repeat;
-use winnow::combinator::separated;
 use winnow::combinator::terminated;
 use winnow::error::ContextError;
 use winnow::error::ErrMode;
@@ -56,6 +56,7 @@ use super::TEXT_QUOTE;
 use super::TRUE;
 use super::UNIT;
 use super::is_delimiter;
+use super::located::Span;
 use crate::type_::Bit;
 use crate::type_::Byte;
 use crate::type_::Call;
@@ -84,50 +85,271 @@ pub trait ParseRepr:
     + From<Call<Self, Self>>
     + From<List<Self>>
     + From<Map<Key, Self>> {
-}
+    // whether the parser should track source spans for locate_keys
+    const LOCATE: bool = false;
 
-#[derive(Default, Copy, Clone, PartialEq, Eq)]
-struct ParseCtx {
-    direction: Direction,
+    // called on every parsed node with its source span
+    fn locate(self, _span: Span) -> Self {
+        self
+    }
+
+    // called on every parsed map with the source spans of its keys
+    fn locate_keys(self, _keys: Map<Key, Span>) -> Self {
+        self
+    }
 }
 
-impl ParseCtx {
+#[derive(Default, Copy, Clone)]
+struct ParseCtx<'a> {
+    direction: Direction,
+    // length of the whole source, for computing offsets from the remaining input
+    len: usize,
+    // collects recovered errors when parsing in recovering mode
+    errors: Option<&'a RefCell<Vec<(usize, ContextError)>>>,
+    // collects spans of outermost comments
+    comments: Option<&'a RefCell<Vec<Span>>>,
+    // right delimiters of the enclosing lists, maps and scopes, as bit flags
+    enclosing: u8,
+    // items of the innermost raw list or raw map are separated by spaces instead of separators
+    raw: bool,
+}
+
+impl<'a> ParseCtx<'a> {
     fn direction(mut self, direction: Direction) -> Self {
         self.direction = direction;
         self
     }
+
+    fn enclose(mut self, right: char) -> Self {
+        self.enclosing |= right_flag(right);
+        self.raw = false;
+        self
+    }
+
+    fn enclose_raw(mut self, right: char) -> Self {
+        self.enclosing |= right_flag(right);
+        self.raw = true;
+        self
+    }
+
+    fn is_enclosing(self, right: char) -> bool {
+        self.enclosing & right_flag(right) != 0
+    }
+
+    // whether the input starts with a right delimiter of the enclosing reprs
+    fn is_right(self, i: &str) -> bool {
+        i.starts_with(|c| self.is_enclosing(c))
+    }
+
+    fn offset(self, i: &str) -> usize {
+        self.len - i.len()
+    }
+
+    fn span(self, start: usize, i: &str) -> Span {
+        Span::new(start, self.offset(i))
+    }
+
+    fn is_recovering(self) -> bool {
+        self.errors.is_some()
+    }
+
+    // records the error and skips the failed item, or returns the error when not recovering
+    fn recover(
+        self, i: &mut &'a str, start: &Checkpoint<&'a str, &'a str>, error: E,
+    ) -> ModalResult<Span> {
+        let Some(errors) = self.errors else {
+            return Err(error);
+        };
+        let (ErrMode::Backtrack(e) | ErrMode::Cut(e)) = error else {
+            return Err(error);
+        };
+        let offset = self.offset(i);
+        errors.borrow_mut().push((offset, e));
+        i.reset(start);
+        let start = self.offset(i);
+        skip_item(self, i, offset - start);
+        Ok(self.span(start, i))
+    }
+
+    // skips what is left of an item and tells whether a separator follows when recovering
+    fn recover_separator(self, i: &mut &'a str, right: char) -> ModalResult<bool> {
+        let Some(errors) = self.errors else {
+            return Ok(false);
+        };
+        let start = i.checkpoint();
+        opt(spaces_comment(self)).parse_next(i)?;
+        if i.is_empty() || self.is_right(i) {
+            i.reset(&start);
+            return Ok(false);
+        }
+        let mut e = ContextError::new();
+        e.push(if self.raw { expect_desc("space") } else { expect_char(SEPARATOR) });
+        e.push(expect_char(right));
+        errors.borrow_mut().push((self.offset(i), e));
+        skip_item(self, i, 0);
+        let separator = if self.raw {
+            opt(spaces_comment(self)).parse_next(i)?
+        } else {
+            opt(trim_comment(self, SEPARATOR).void()).parse_next(i)?
+        };
+        Ok(separator.is_some())
+    }
+
+    // a missing right delimiter is assumed at the end of the source
+    // or before a right delimiter of the enclosing reprs when recovering
+    fn assume_right(self, i: &str, right: char) -> bool {
+        let Some(errors) = self.errors else {
+            return false;
+        };
+        if i.starts_with(right) {
+            return false;
+        }
+        if !i.is_empty() && !self.is_right(i) {
+            return false;
+        }
+        let mut e = ContextError::new();
+        e.push(expect_char(right));
+        errors.borrow_mut().push((self.offset(i), e));
+        true
+    }
+}
+
+fn right_flag(right: char) -> u8 {
+    match right {
+        LIST_RIGHT => 1,
+        MAP_RIGHT => 2,
+        SCOPE_RIGHT => 4,
+        _ => 0,
+    }
 }
 
 type E = ErrMode<ContextError>;
 
 pub fn parse<T: ParseRepr>(src: &str) -> Result<T, super::ParseError> {
-    top::<T>.parse(src).map_err(|e| super::ParseError { msg: e.to_string() })
-}
-
-fn label(label: &'static str) -> StrContext {
+    let ctx = ParseCtx { len: src.len(), ..ParseCtx::default() };
+    top::<T>(ctx).parse(src).map_err(|e| super::ParseError::new(src, e.offset(), e.inner()))
+}
+
+// parse as much as possible, failed items of lists and maps are replaced by unit
+// and missing right delimiters are assumed
+pub fn parse_recover<T: ParseRepr>(src: &str) -> (T, Vec<super::ParseError>) {
+    let errors = RefCell::new(Vec::new());
+    let ctx = ParseCtx { len: src.len(), errors: Some(&errors), ..ParseCtx::default() };
+    let repr = match top::<T>(ctx).parse(src) {
+        Ok(repr) => repr,
+        Err(e) => {
+            errors.borrow_mut().push((e.offset(), e.into_inner()));
+            T::from(Unit)
+        },
+    };
+    let errors = errors.into_inner().into_iter();
+    let errors = errors.map(|(offset, e)| super::ParseError::new(src, offset, &e)).collect();
+    (repr, errors)
+}
+
+// parse and also return the spans of all comments, in source order
+pub(crate) fn parse_with_comments<T: ParseRepr>(
+    src: &str,
+) -> Result<(T, Vec<Span>), super::ParseError> {
+    let comments = RefCell::new(Vec::new());
+    let ctx = ParseCtx { len: src.len(), comments: Some(&comments), ..ParseCtx::default() };
+    let repr = top::<T>(ctx)
+        .parse(src)
+        .map_err(|e| super::ParseError::new(src, e.offset(), e.inner()))?;
+    // comments may be parsed more than once because of backtracking
+    let mut comments = comments.into_inner();
+    comments.sort_by_key(|span| span.start);
+    comments.dedup();
+    Ok((repr, comments))
+}
+
+pub(super) fn label(label: &'static str) -> StrContext {
     StrContext::Label(label)
 }
 
-fn expect_desc(description: &'static str) -> StrContext {
+pub(super) fn expect_desc(description: &'static str) -> StrContext {
     StrContext::Expected(StrContextValue::Description(description))
 }
 
-fn expect_char(c: char) -> StrContext {
+pub(super) fn expect_char(c: char) -> StrContext {
     StrContext::Expected(StrContextValue::CharLiteral(c))
 }
 
-fn cut_expect_desc(description: &'static str) -> E {
+pub(super) fn cut_expect_desc(description: &'static str) -> E {
     let mut ctx = ContextError::new();
     ctx.push(expect_desc(description));
     ErrMode::Cut(ctx)
 }
 
-fn top<T: ParseRepr>(src: &mut &str) -> ModalResult<T> {
-    let ctx = ParseCtx::default();
-    trim_comment(ctx, compose(ctx)).parse_next(src)
+fn duplicate_key() -> E {
+    let mut ctx = ContextError::new();
+    ctx.push(expect_desc("no duplicate keys"));
+    ErrMode::Backtrack(ctx)
+}
+
+fn top<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    trim_comment(ctx, compose(ctx))
 }
 
-fn trim_comment<'a, O, F>(ctx: ParseCtx, f: F) -> impl Parser<&'a str, O, E>
+// skip to the next separator or an enclosing right delimiter, scanning from the failed offset
+fn skip_item(ctx: ParseCtx, i: &mut &str, failed: usize) {
+    // all delimiters are ascii, so it's safe to scan bytes
+    let bytes = i.as_bytes();
+    let mut index = 0;
+    // don't resume inside a quoted literal
+    while index < failed.min(bytes.len()) {
+        match bytes[index] as char {
+            TEXT_QUOTE | KEY_QUOTE => index = skip_quote(i, index),
+            _ => index += 1,
+        }
+    }
+    index = index.max(failed);
+    let mut rights = Vec::new();
+    while index < bytes.len() {
+        let c = bytes[index] as char;
+        match c {
+            LIST_LEFT => rights.push(LIST_RIGHT),
+            MAP_LEFT => rights.push(MAP_RIGHT),
+            SCOPE_LEFT => rights.push(SCOPE_RIGHT),
+            LIST_RIGHT | MAP_RIGHT | SCOPE_RIGHT => {
+                // missing right delimiters of nested items are assumed
+                if let Some(n) = rights.iter().rposition(|r| *r == c) {
+                    rights.truncate(n);
+                } else if ctx.is_enclosing(c) {
+                    break;
+                }
+                // other unmatched right delimiters are skipped
+            },
+            SEPARATOR if rights.is_empty() && !ctx.raw => break,
+            ' ' | '\t' | '\r' | '\n' if rights.is_empty() && ctx.raw => break,
+            TEXT_QUOTE | KEY_QUOTE => {
+                index = skip_quote(i, index);
+                continue;
+            },
+            _ => {},
+        }
+        index += 1;
+    }
+    *i = &i[index.min(bytes.len()) ..];
+}
+
+// returns the index after the quoted literal starting at index
+fn skip_quote(i: &str, index: usize) -> usize {
+    let bytes = i.as_bytes();
+    let quote = bytes[index];
+    let mut close = index + 1;
+    while close < bytes.len() && bytes[close] != quote {
+        close += if bytes[close] == b'^' { 2 } else { 1 };
+    }
+    // an unpaired quote only spans the current line
+    if close < bytes.len() {
+        close + 1
+    } else {
+        i[index ..].find('\n').map_or(bytes.len(), |n| index + n)
+    }
+}
+
+fn trim_comment<'a, O, F>(ctx: ParseCtx<'a>, f: F) -> impl Parser<&'a str, O, E>
 where F: Parser<&'a str, O, E> {
     delimited(opt(spaces_comment(ctx)), f, opt(spaces_comment(ctx)))
 }
@@ -143,17 +365,23 @@ fn space_tab0(i: &mut &str) -> ModalResult<()> {
     f.context(label("space_tab0")).parse_next(i)
 }
 
-fn spaces_comment<'a>(ctx: ParseCtx) -> impl Parser<&'a str, (), E> {
+fn spaces_comment<'a>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, (), E> {
     repeat(1 .., alt((spaces, comment(ctx))))
 }
 
-fn comment<'a>(ctx: ParseCtx) -> impl Parser<&'a str, (), E> {
-    let comment_tokens = repeat(0 .., comment_token(ctx));
+fn comment<'a>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, (), E> {
+    let inner = ParseCtx { comments: None, ..ctx };
+    let comment_tokens = repeat(0 .., comment_token(inner));
     let f = preceded(EMPTY, delimited_cut(SCOPE_LEFT, comment_tokens, SCOPE_RIGHT));
+    let f = located(ctx, f).map(move |((), span)| {
+        if let Some(comments) = ctx.comments {
+            comments.borrow_mut().push(span);
+        }
+    });
     f.context(label("comment"))
 }
 
-fn comment_token<'a>(ctx: ParseCtx) -> impl Parser<&'a str, (), E> {
+fn comment_token<'a>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, (), E> {
     // to avoid error[E0720]: cannot resolve opaque type
     move |i: &mut _| {
         alt((spaces, SEPARATOR.void(), comment(ctx), token::<C>(ctx).void())).parse_next(i)
@@ -185,19 +413,26 @@ where F: Parser<&'a str, T, E> {
 }
 
 fn delimited_trim_comment<'a, T, F>(
-    ctx: ParseCtx, left: char, f: F, right: char,
+    ctx: ParseCtx<'a>, left: char, f: F, right: char,
 ) -> impl Parser<&'a str, T, E>
 where F: Parser<&'a str, T, E> {
-    delimited_cut(left, trim_comment(ctx, f), right)
+    let left = left.context(expect_char(left));
+    let right = move |i: &mut &'a str| {
+        if ctx.assume_right(i, right) {
+            return Ok(());
+        }
+        right.void().context(expect_char(right)).parse_next(i)
+    };
+    delimited(left, cut_err(trim_comment(ctx, f)), cut_err(right))
 }
 
-fn scoped_trim_comment<'a, T, F>(ctx: ParseCtx, f: F) -> impl Parser<&'a str, T, E>
+fn scoped_trim_comment<'a, T, F>(ctx: ParseCtx<'a>, f: F) -> impl Parser<&'a str, T, E>
 where F: Parser<&'a str, T, E> {
     delimited_trim_comment(ctx, SCOPE_LEFT, f, SCOPE_RIGHT)
 }
 
-fn scope<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    scoped_trim_comment(ctx, compose(ctx)).context(label("scope"))
+fn scope<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    scoped_trim_comment(ctx, compose(ctx.enclose(SCOPE_RIGHT))).context(label("scope"))
 }
 
 fn trivial_key1<'a>(i: &mut &'a str) -> ModalResult<&'a str> {
@@ -212,7 +447,16 @@ fn is_key(c: char) -> bool {
     Key::is_key(c)
 }
 
-fn token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, Token<'a, T>, E> {
+fn located<'a, O, F>(ctx: ParseCtx<'a>, mut f: F) -> impl Parser<&'a str, (O, Span), E>
+where F: Parser<&'a str, O, E> {
+    move |i: &mut &'a str| {
+        let start = ctx.offset(i);
+        let o = f.parse_next(i)?;
+        Ok((o, ctx.span(start, i)))
+    }
+}
+
+fn token<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, Token<'a, T>, E> {
     let f = move |i: &mut _| match peek(any).parse_next(i)? {
         LIST_LEFT => list(ctx).map(Token::Default).parse_next(i),
         LIST_RIGHT => fail.parse_next(i),
@@ -227,10 +471,14 @@ fn token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, Token<'a, T>,
         '0' ..= '9' => number.map(Token::Default).parse_next(i),
         _ => cut_err(key_token(ctx)).parse_next(i),
     };
+    let f = located(ctx, f).map(|(token, span)| match token {
+        Token::Default(token) => Token::Default(T::locate(token, span)),
+        token => token,
+    });
     f.context(label("token"))
 }
 
-fn key_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, Token<'a, T>, E> {
+fn key_token<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, Token<'a, T>, E> {
     move |i: &mut _| {
         let i: &mut &str = i;
         let checkpoint = i.checkpoint();
@@ -252,7 +500,7 @@ fn key_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, Token<'a,
 
 const LEFT_DELIMITERS: [char; 5] = [SCOPE_LEFT, LIST_LEFT, MAP_LEFT, KEY_QUOTE, TEXT_QUOTE];
 
-fn prefix<'a, T: ParseRepr>(prefix: &str, ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
+fn prefix<'a, T: ParseRepr>(prefix: &str, ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
     move |i: &mut _| {
         let i: &mut &str = i;
         match prefix {
@@ -272,61 +520,68 @@ fn prefix<'a, T: ParseRepr>(prefix: &str, ctx: ParseCtx) -> impl Parser<&'a str,
     }
 }
 
-fn compose<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
+fn compose<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
     move |i: &mut _| match ctx.direction {
         Direction::Left => compose_left(ctx).parse_next(i),
         Direction::Right => compose_right(ctx).parse_next(i),
     }
 }
 
-fn compose_left<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    move |i: &mut _| {
+fn compose_left<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    move |i: &mut &'a str| {
         let mut input = preceded(spaces_comment(ctx), input_token(ctx));
         let mut func = preceded(spaces_comment(ctx), func_token(ctx));
+        let start = ctx.offset(i);
         let left = input_token(ctx).parse_next(i)?;
         let Some(middle) = opt(func.by_ref()).parse_next(i)? else {
             return input_repr(i, left);
         };
         let right = input.parse_next(i)?;
-        let mut left = compose_one(ctx, i, left, middle, right)?;
+        let mut left = compose_one(ctx, i, left, middle, right, start)?;
         loop {
             let Some(middle) = opt(func.by_ref()).parse_next(i)? else {
                 return Ok(left);
             };
             let right = input.parse_next(i)?;
-            left = compose_one_left(ctx, left, middle, right);
+            left = compose_one_left(left, middle, right, ctx.span(start, i));
         }
     }
 }
 
-fn compose_right<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    move |i: &mut _| {
+fn compose_right<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    move |i: &mut &'a str| {
+        let start = ctx.offset(i);
         let left = input_token(ctx).parse_next(i)?;
         let Some(middle) = opt(preceded(spaces_comment(ctx), func_token(ctx))).parse_next(i)?
         else {
             return input_repr(i, left);
         };
-        compose_right_recursive(ctx, i, left, middle)
+        compose_right_recursive(ctx, i, left, middle, start)
     }
 }
 
 fn compose_right_recursive<'a, T: ParseRepr>(
-    ctx: ParseCtx, i: &mut &'a str, left: InputToken<'a, T>, middle: FuncToken<T>,
+    ctx: ParseCtx<'a>, i: &mut &'a str, left: InputToken<'a, T>, middle: FuncToken<T>, start: usize,
 ) -> ModalResult<T> {
-    let right = preceded(spaces_comment(ctx), input_token(ctx)).parse_next(i)?;
+    spaces_comment(ctx).parse_next(i)?;
+    let right_start = ctx.offset(i);
+    let right = input_token(ctx).parse_next(i)?;
     let Some(middle2) = opt(preceded(spaces_comment(ctx), func_token(ctx))).parse_next(i)? else {
-        return compose_one(ctx, i, left, middle, right);
+        return compose_one(ctx, i, left, middle, right, start);
     };
-    let right = compose_right_recursive(ctx, i, right, middle2)?;
-    Ok(compose_one_right(ctx, left, middle, right))
+    let right = compose_right_recursive(ctx, i, right, middle2, right_start)?;
+    Ok(compose_one_right(left, middle, right, ctx.span(start, i)))
 }
 
 fn compose_one<'a, T: ParseRepr>(
-    ctx: ParseCtx, i: &mut &'a str, left: InputToken<'a, T>, func: FuncToken<T>,
-    right: InputToken<'a, T>,
+    ctx: ParseCtx<'a>, i: &mut &'a str, left: InputToken<'a, T>, func: FuncToken<T>,
+    right: InputToken<'a, T>, start: usize,
 ) -> ModalResult<T> {
+    let span = ctx.span(start, i);
     let input = match (left, right) {
-        (InputToken::Default(left), InputToken::Default(right)) => T::from(Pair::new(left, right)),
+        (InputToken::Default(left), InputToken::Default(right)) => {
+            T::from(Pair::new(left, right)).locate(span)
+        },
         (InputToken::Default(left), InputToken::Empty(_)) => left,
         (InputToken::Empty(_), InputToken::Default(right)) => right,
         (InputToken::Empty(_), InputToken::Empty(checkpoint)) => {
@@ -334,33 +589,33 @@ fn compose_one<'a, T: ParseRepr>(
             return Err(cut_expect_desc(concatcp!("at most one ", EMPTY)));
         },
     };
-    Ok(compose_func_input(ctx, func, input))
+    Ok(compose_func_input(func, input, span))
 }
 
 fn compose_one_left<T: ParseRepr>(
-    ctx: ParseCtx, left: T, func: FuncToken<T>, right: InputToken<T>,
+    left: T, func: FuncToken<T>, right: InputToken<T>, span: Span,
 ) -> T {
     let input = match right {
-        InputToken::Default(right) => T::from(Pair::new(left, right)),
+        InputToken::Default(right) => T::from(Pair::new(left, right)).locate(span),
         InputToken::Empty(_) => left,
     };
-    compose_func_input(ctx, func, input)
+    compose_func_input(func, input, span)
 }
 
 fn compose_one_right<T: ParseRepr>(
-    ctx: ParseCtx, left: InputToken<T>, func: FuncToken<T>, right: T,
+    left: InputToken<T>, func: FuncToken<T>, right: T, span: Span,
 ) -> T {
     let input = match left {
-        InputToken::Default(left) => T::from(Pair::new(left, right)),
+        InputToken::Default(left) => T::from(Pair::new(left, right)).locate(span),
         InputToken::Empty(_) => right,
     };
-    compose_func_input(ctx, func, input)
+    compose_func_input(func, input, span)
 }
 
-fn compose_func_input<T: ParseRepr>(_ctx: ParseCtx, func: FuncToken<T>, input: T) -> T {
+fn compose_func_input<T: ParseRepr>(func: FuncToken<T>, input: T, span: Span) -> T {
     match func {
         FuncToken::Pair => input,
-        FuncToken::Default(func) => T::from(Call::new(func, input)),
+        FuncToken::Default(func) => T::from(Call::new(func, input)).locate(span),
     }
 }
 
@@ -383,7 +638,7 @@ enum InputToken<'a, T> {
 const QUOTE_EMPTY: &str = concatcp!(KEY_QUOTE, EMPTY, KEY_QUOTE);
 const QUOTE_PAIR: &str = concatcp!(KEY_QUOTE, PAIR, KEY_QUOTE);
 
-fn func_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, FuncToken<T>, E> {
+fn func_token<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, FuncToken<T>, E> {
     move |i: &mut _| match token(ctx).parse_next(i)? {
         Token::Empty(checkpoint) => {
             i.reset(&checkpoint);
@@ -394,7 +649,7 @@ fn func_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, FuncToken
     }
 }
 
-fn input_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, InputToken<'a, T>, E> {
+fn input_token<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, InputToken<'a, T>, E> {
     move |i: &mut _| match token(ctx).parse_next(i)? {
         Token::Empty(checkpoint) => Ok(InputToken::Empty(checkpoint)),
         Token::Pair(checkpoint) => {
@@ -405,7 +660,7 @@ fn input_token<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, InputTok
     }
 }
 
-fn repr<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
+fn repr<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
     move |i: &mut _| match token(ctx).parse_next(i)? {
         Token::Empty(checkpoint) => {
             i.reset(&checkpoint);
@@ -429,21 +684,30 @@ fn input_repr<'a, T: ParseRepr>(i: &mut &'a str, input: InputToken<'a, T>) -> Mo
     }
 }
 
-fn cell<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
+fn cell<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
     scope(ctx).map(|v| T::from(Cell::new(v))).context(label("cell"))
 }
 
-fn list<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    let items = move |i: &mut _| {
+fn list<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    let items = move |i: &mut &'a str| {
+        let ctx = ctx.enclose(LIST_RIGHT);
         let mut list = Vec::new();
         let mut repr = opt(compose(ctx));
         let mut separator = opt(trim_comment(ctx, SEPARATOR.context(expect_char(SEPARATOR))));
         loop {
-            let Some(item) = repr.parse_next(i)? else {
-                break;
+            let checkpoint = i.checkpoint();
+            let item = if ctx.is_recovering() && !i.is_empty() && !ctx.is_right(i) {
+                compose(ctx).parse_next(i).map(Some)
+            } else {
+                repr.parse_next(i)
+            };
+            let item = match item {
+                Ok(Some(item)) => item,
+                Ok(None) => break,
+                Err(e) => T::from(Unit).locate(ctx.recover(i, &checkpoint, e)?),
             };
             list.push(item);
-            if separator.parse_next(i)?.is_none() {
+            if separator.parse_next(i)?.is_none() && !ctx.recover_separator(i, LIST_RIGHT)? {
                 break;
             }
         }
@@ -453,60 +717,124 @@ fn list<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
     f.context(label("list"))
 }
 
-fn raw_list<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    let repr_list = separated(0 .., repr::<T>(ctx), spaces_comment(ctx))
-        .map(|tokens: Vec<_>| T::from(List::from(tokens)));
-    let f = delimited_trim_comment(ctx, LIST_LEFT, repr_list, LIST_RIGHT);
+fn raw_list<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    let items = move |i: &mut &'a str| {
+        let ctx = ctx.enclose_raw(LIST_RIGHT);
+        let mut list = Vec::new();
+        let mut token = opt(repr(ctx));
+        let mut separator = opt(spaces_comment(ctx));
+        loop {
+            let checkpoint = i.checkpoint();
+            let item = if ctx.is_recovering() && !i.is_empty() && !ctx.is_right(i) {
+                repr(ctx).parse_next(i).map(Some)
+            } else {
+                token.parse_next(i)
+            };
+            let item = match item {
+                Ok(Some(item)) => item,
+                Ok(None) => break,
+                Err(e) => T::from(Unit).locate(ctx.recover(i, &checkpoint, e)?),
+            };
+            list.push(item);
+            if separator.parse_next(i)?.is_none() && !ctx.recover_separator(i, LIST_RIGHT)? {
+                break;
+            }
+        }
+        Ok(T::from(List::from(list)))
+    };
+    let f = delimited_trim_comment(ctx, LIST_LEFT, items, LIST_RIGHT);
     f.context(label("raw list"))
 }
 
-fn map<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    let items = move |i: &mut _| {
+fn map<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    let items = move |i: &mut &'a str| {
+        let ctx = ctx.enclose(MAP_RIGHT);
         let mut map = Map::default();
-        let mut key = opt(any_key);
+        let mut keys = Map::default();
+        let mut key = opt(located(ctx, any_key));
         let mut pair = opt(preceded(spaces_comment(ctx), PAIR.void()));
         let mut value = cut_err(preceded(
             spaces_comment(ctx).context(expect_desc("space")),
             compose(ctx).context(expect_desc("value")),
         ));
         let mut separator = opt(trim_comment(ctx, SEPARATOR.context(expect_char(SEPARATOR))));
-        let mut duplicate = fail.context(expect_desc("no duplicate keys"));
         loop {
-            let Some(k) = key.parse_next(i)? else {
-                break;
-            };
-            if map.contains_key(&k) {
-                return duplicate.parse_next(i);
-            }
-            let v = if pair.parse_next(i).unwrap().is_none() {
-                T::from(Unit)
+            let checkpoint = i.checkpoint();
+            let k = if ctx.is_recovering() && !i.is_empty() && !ctx.is_right(i) {
+                located(ctx, any_key).parse_next(i).map(Some)
             } else {
-                value.parse_next(i)?
+                key.parse_next(i)
             };
-            map.insert(k, v);
-            if separator.parse_next(i)?.is_none() {
+            match k {
+                Ok(Some((k, _))) if map.contains_key(&k) => {
+                    ctx.recover(i, &checkpoint, duplicate_key())?;
+                },
+                Ok(Some((k, span))) => {
+                    if T::LOCATE {
+                        keys.insert(k.clone(), span);
+                    }
+                    let v = if pair.parse_next(i)?.is_none() {
+                        T::from(Unit).locate(Span::new(span.end, span.end))
+                    } else {
+                        let checkpoint = i.checkpoint();
+                        match value.parse_next(i) {
+                            Ok(v) => v,
+                            Err(e) => {
+                                T::from(Unit).locate(ctx.recover(i, &checkpoint, e)?)
+                            },
+                        }
+                    };
+                    map.insert(k, v);
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    ctx.recover(i, &checkpoint, e)?;
+                },
+            }
+            if separator.parse_next(i)?.is_none() && !ctx.recover_separator(i, MAP_RIGHT)? {
                 break;
             }
         }
-        Ok(T::from(map))
+        Ok(T::from(map).locate_keys(keys))
     };
     let f = delimited_trim_comment(ctx, MAP_LEFT, items, MAP_RIGHT);
     f.context(label("map"))
 }
 
-fn raw_map<'a, T: ParseRepr>(ctx: ParseCtx) -> impl Parser<&'a str, T, E> {
-    let items = move |i: &mut _| {
-        let kv = (any_key, spaces_comment(ctx), repr::<T>(ctx));
-        let tokens: Vec<_> = separated(0 .., kv, spaces_comment(ctx)).parse_next(i)?;
-        let mut duplicate = fail.context(expect_desc("no duplicate keys"));
-        let mut map = Map::with_capacity(tokens.len());
-        for (key, (), value) in tokens {
-            if map.contains_key(&key) {
-                return duplicate.parse_next(i);
+fn raw_map<'a, T: ParseRepr>(ctx: ParseCtx<'a>) -> impl Parser<&'a str, T, E> {
+    let items = move |i: &mut &'a str| {
+        let ctx = ctx.enclose_raw(MAP_RIGHT);
+        let mut map = Map::default();
+        let mut keys = Map::default();
+        let mut kv = (located(ctx, any_key), spaces_comment(ctx), repr::<T>(ctx));
+        let mut separator = opt(spaces_comment(ctx));
+        loop {
+            let checkpoint = i.checkpoint();
+            let item = if ctx.is_recovering() && !i.is_empty() && !ctx.is_right(i) {
+                kv.parse_next(i).map(Some)
+            } else {
+                opt(kv.by_ref()).parse_next(i)
+            };
+            match item {
+                Ok(Some(((key, _), (), _))) if map.contains_key(&key) => {
+                    ctx.recover(i, &checkpoint, duplicate_key())?;
+                },
+                Ok(Some(((key, span), (), value))) => {
+                    if T::LOCATE {
+                        keys.insert(key.clone(), span);
+                    }
+                    map.insert(key, value);
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    ctx.recover(i, &checkpoint, e)?;
+                },
+            }
+            if separator.parse_next(i)?.is_none() && !ctx.recover_separator(i, MAP_RIGHT)? {
+                break;
             }
-            map.insert(key, value);
         }
-        Ok(T::from(map))
+        Ok(T::from(map).locate_keys(keys))
     };
     let f = delimited_trim_comment(ctx, MAP_LEFT, items, MAP_RIGHT);
     f.context(label("raw map"))
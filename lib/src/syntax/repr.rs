@@ -125,6 +125,14 @@ impl FmtRepr for Repr {
         matches!(self, Repr::Pair(_))
     }
 
+    fn is_list(&self) -> bool {
+        matches!(self, Repr::List(_))
+    }
+
+    fn is_map(&self) -> bool {
+        matches!(self, Repr::Map(_))
+    }
+
     fn to_pair(&self) -> Pair<&dyn FmtRepr, &dyn FmtRepr> {
         let Repr::Pair(pair) = self else {
             panic!("called `FmtRepr::to_pair()` on non-pair value")
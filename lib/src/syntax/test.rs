@@ -4,10 +4,21 @@ use std::str::FromStr;
 use bigdecimal::BigDecimal;
 use num_bigint::BigInt;
 use num_traits::Num;
-
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use super::FmtCtx;
+use super::LocatedRepr;
+use super::TrailingSeparator;
+use super::cst::Cst;
+use super::cst::CstStep;
+use super::cst::CstTokenKind;
+use super::located::LocatedNode;
 use super::repr::CellRepr;
 use super::repr::PairRepr;
 use super::repr::Repr;
+use crate::cfg::lib::value::Arbitrary;
+use crate::semantics::val::Val;
 use crate::test::parse_test_file;
 use crate::type_::Bit;
 use crate::type_::Call;
@@ -106,6 +117,8 @@ fn test_generate(src: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
             eprintln!("file {file_name} case ({title}) src({s}): parse failed\n{e}");
             e
         })?;
+        let never = FmtCtx::default().trailing_separator(TrailingSeparator::Never);
+        let vertical = FmtCtx::default().trailing_separator(TrailingSeparator::Vertical);
         let fmt_list = [
             format!("{repr}"),
             format!("{repr:#}"),
@@ -123,6 +136,12 @@ fn test_generate(src: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
             format!("{repr:>#}"),
             format!("{repr:>?}"),
             format!("{repr:>#?}"),
+            format!("{:#}", FmtCtx::default().width(0).display(&repr)),
+            format!("{:<#}", FmtCtx::default().width(16).indent(2).display(&repr)),
+            format!("{:>#}", FmtCtx::default().width(40).sort_keys(true).display(&repr)),
+            format!("{:#}", never.display(&repr)),
+            format!("{}", never.display(&repr)),
+            format!("{:#}", vertical.width(24).display(&repr)),
         ];
         for repr_str in fmt_list {
             let new_repr = repr_str.parse().map_err(|e| {
@@ -165,6 +184,268 @@ fn test_parse_bad(src: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn test_located(src: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
+    for [title, s] in parse_test_file::<2>(src, file_name) {
+        let located: LocatedRepr = s.parse()?;
+        let repr: Repr = s.parse()?;
+        assert_eq!(
+            Repr::from(located.clone()),
+            repr,
+            "file {file_name} case ({title}) src({s}): located repr not equal"
+        );
+        check_span(s, &located, &format!("file {file_name} case ({title})"));
+    }
+    Ok(())
+}
+
+fn check_span(src: &str, located: &LocatedRepr, case: &str) {
+    let span = located.span;
+    let children: Vec<&LocatedRepr> = match &located.node {
+        LocatedNode::Cell(cell) => vec![&cell.value],
+        LocatedNode::Pair(pair) => vec![&pair.left, &pair.right],
+        LocatedNode::Call(call) => vec![&call.func, &call.input],
+        LocatedNode::List(list) => list.iter().collect(),
+        LocatedNode::Map(map) => {
+            for (key, entry) in &**map {
+                let key_src = &src[entry.key.start .. entry.key.end];
+                assert!(
+                    span.contains(entry.key) && (key_src == &**key || key_src.starts_with('\'')),
+                    "{case}: key {key} located at ({key_src})"
+                );
+            }
+            map.values().map(|entry| &entry.value).collect()
+        },
+        _ if span.is_empty() => return,
+        _ => {
+            let leaf: Repr = src[span.start .. span.end].parse().expect(case);
+            assert_eq!(leaf, Repr::from(located.clone()), "{case}: leaf span");
+            return;
+        },
+    };
+    for child in children {
+        assert!(span.contains(child.span), "{case}: child {child} out of span of {located}");
+        check_span(src, child, case);
+    }
+}
+
+#[test]
+fn test_parse_located() -> Result<(), Box<dyn Error>> {
+    test_located(include_str!("test/unit.air"), "test/unit.air")?;
+    test_located(include_str!("test/bit.air"), "test/bit.air")?;
+    test_located(include_str!("test/key.air"), "test/key.air")?;
+    test_located(include_str!("test/text.air"), "test/text.air")?;
+    test_located(include_str!("test/integer.air"), "test/integer.air")?;
+    test_located(include_str!("test/decimal.air"), "test/decimal.air")?;
+    test_located(include_str!("test/byte.air"), "test/byte.air")?;
+    test_located(include_str!("test/cell.air"), "test/cell.air")?;
+    test_located(include_str!("test/pair.air"), "test/pair.air")?;
+    test_located(include_str!("test/call.air"), "test/call.air")?;
+    test_located(include_str!("test/list.air"), "test/list.air")?;
+    test_located(include_str!("test/map.air"), "test/map.air")?;
+    test_located(include_str!("test/scope.air"), "test/scope.air")?;
+    test_located(include_str!("test/space.air"), "test/space.air")?;
+    test_located(include_str!("test/doc.air"), "test/doc.air")
+}
+
+#[test]
+fn test_parse_located_span() -> Result<(), Box<dyn Error>> {
+    let src = "_( comment ) {a : [1, b c d], 'x y' : .(e)}";
+    let located: LocatedRepr = src.parse()?;
+    let LocatedNode::Map(map) = &located.node else {
+        panic!("expected map");
+    };
+    let span_src = |span: super::Span| &src[span.start .. span.end];
+    assert_eq!(span_src(located.span), "{a : [1, b c d], 'x y' : .(e)}", "map span");
+    let a = &map[&Key::from_str_unchecked("a")];
+    assert_eq!(span_src(a.key), "a", "key span");
+    assert_eq!(span_src(a.value.span), "[1, b c d]", "list span");
+    let LocatedNode::List(list) = &a.value.node else {
+        panic!("expected list");
+    };
+    assert_eq!(span_src(list[1].span), "b c d", "call span");
+    let xy = &map[&Key::from_str_unchecked("x y")];
+    assert_eq!(span_src(xy.key), "'x y'", "quoted key span");
+    assert_eq!(span_src(xy.value.span), ".(e)", "cell span");
+    Ok(())
+}
+
+fn test_cst(src: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
+    for [title, s] in parse_test_file::<2>(src, file_name) {
+        let cst: Cst = s.parse()?;
+        assert_eq!(cst.to_string(), s, "file {file_name} case ({title}): re-emit");
+        let mut pos = 0;
+        for token in cst.tokens() {
+            assert_eq!(token.span.start, pos, "file {file_name} case ({title}): token {token:?}");
+            pos = token.span.end;
+        }
+        assert_eq!(pos, s.len(), "file {file_name} case ({title}): tokens cover source");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cst_lossless() -> Result<(), Box<dyn Error>> {
+    test_cst(include_str!("test/cell.air"), "test/cell.air")?;
+    test_cst(include_str!("test/pair.air"), "test/pair.air")?;
+    test_cst(include_str!("test/call.air"), "test/call.air")?;
+    test_cst(include_str!("test/list.air"), "test/list.air")?;
+    test_cst(include_str!("test/map.air"), "test/map.air")?;
+    test_cst(include_str!("test/scope.air"), "test/scope.air")?;
+    test_cst(include_str!("test/space.air"), "test/space.air")?;
+    test_cst(include_str!("test/doc.air"), "test/doc.air")
+}
+
+#[test]
+fn test_cst_tokens() -> Result<(), Box<dyn Error>> {
+    let cst: Cst = "_(c) {a : (x), b}".parse()?;
+    let tokens: Vec<_> = cst.tokens().iter().map(|t| (t.kind, cst.text(t.span))).collect();
+    let expected = [
+        (CstTokenKind::Comment, "_(c)"),
+        (CstTokenKind::Space, " "),
+        (CstTokenKind::Punct, "{"),
+        (CstTokenKind::Key, "a"),
+        (CstTokenKind::Space, " "),
+        (CstTokenKind::Punct, ":"),
+        (CstTokenKind::Space, " "),
+        (CstTokenKind::Punct, "("),
+        (CstTokenKind::Atom, "x"),
+        (CstTokenKind::Punct, ")"),
+        (CstTokenKind::Punct, ","),
+        (CstTokenKind::Space, " "),
+        (CstTokenKind::Key, "b"),
+        (CstTokenKind::Punct, "}"),
+    ];
+    assert_eq!(tokens, expected, "tokens");
+    Ok(())
+}
+
+#[test]
+fn test_cst_edit() -> Result<(), Box<dyn Error>> {
+    let src = "_( config ) {\n    a : 1, _( keep me )\n    b : [x,   y],\n    c\n}\n";
+    let mut cst: Cst = src.parse()?;
+    let step = |key: &str| CstStep::Key(Key::from_str_unchecked(key));
+    assert!(cst.set(&[step("a")], &"f : g".parse()?), "set");
+    assert!(cst.set(&[step("c")], &"2".parse()?), "set omitted value");
+    assert!(cst.push(&[step("b")], &"z".parse()?), "push");
+    assert!(cst.remove(&[step("b"), CstStep::Index(0)]), "remove item");
+    assert!(cst.insert(&[], Key::from_str_unchecked("d"), &"[]".parse()?), "insert");
+    assert!(cst.push(&[step("d")], &"w".parse()?), "push");
+    assert!(!cst.set(&[step("a"), CstStep::Index(0)], &"1".parse()?), "invalid path");
+    let expected =
+        "_( config ) {\n    a : f : g, _( keep me )\n    b : [y, z],\n    c : 2, d : [w]\n}\n";
+    assert_eq!(cst.source(), expected, "edited source");
+    assert!(cst.remove(&[step("a")]), "remove entry");
+    assert!(cst.remove(&[step("c")]), "remove entry");
+    let expected = "_( config ) {\n    _( keep me )\n    b : [y, z],\n    d : [w]\n}\n";
+    assert_eq!(cst.source(), expected, "removed source");
+    let mut cst: Cst = "{a : 1, _(c)b : 2}".parse()?;
+    assert!(cst.remove(&[step("a")]), "remove entry before comment");
+    assert_eq!(cst.source(), "{_(c)b : 2}", "comment of the next entry");
+    let mut cst: Cst = "_[x _(c) y z]".parse()?;
+    assert!(cst.remove(&[CstStep::Index(0)]), "remove raw item before comment");
+    assert_eq!(cst.source(), "_[_(c) y z]", "comment of the next raw item");
+    Ok(())
+}
+
+#[test]
+fn test_generate_map_order() -> Result<(), Box<dyn Error>> {
+    let repr: Repr = "{c : 1, a : [z, y, x], b : {e, d}}".parse()?;
+    let expected = "{ c : 1, a : [ z, y, x, ], b : { e : ., d : ., }, }";
+    assert_eq!(format!("{repr}"), expected, "insertion order");
+    let sorted = FmtCtx::default().sort_keys(true);
+    let expected = "{ a : [ z, y, x, ], b : { d : ., e : ., }, c : 1, }";
+    assert_eq!(format!("{}", sorted.display(&repr)), expected, "sorted keys");
+    Ok(())
+}
+
+#[test]
+fn test_cst_format() -> Result<(), Box<dyn Error>> {
+    let src = "_( head )\n_ do [ _( first )\n  a set {x : 1,  _(x) y : [1,2], z}, _( same line )\n \
+        b set (c : _(d) e), _( end ) ] _( tail )";
+    let cst: Cst = src.parse()?;
+    let ctx = FmtCtx::default().width(100);
+    let formatted = cst.format(ctx);
+    let expected = "_( head )\n_ do [\n    _( first )\n    a set {\n        x : 1, _(x)\n        \
+        y : [ 1, 2, ],\n        z,\n    }, _( same line )\n    b set (c : _(d) e), _( end )\n] _( tail )";
+    assert_eq!(formatted, expected, "format");
+    let cst: Cst = formatted.parse()?;
+    assert_eq!(cst.format(ctx), expected, "format again");
+    let cst: Cst = "_ do [ a ,b ] _( tail )".parse()?;
+    assert_eq!(cst.format(ctx), "_ do [ a, b, ] _( tail )", "format without comments");
+    for src in ["<(_ctx : i : i)", "[<(_ctx : i : i), >(a b c)]"] {
+        let formatted = src.parse::<Cst>()?.format(ctx);
+        assert_eq!(formatted.parse::<Cst>()?.format(ctx), formatted, "src({src}): format again");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cst_format_idempotent() -> Result<(), Box<dyn Error>> {
+    let files = [
+        (include_str!("../test/bit.air"), "test/bit.air"),
+        (include_str!("../test/byte.air"), "test/byte.air"),
+        (include_str!("../test/call.air"), "test/call.air"),
+        (include_str!("../test/cell.air"), "test/cell.air"),
+        (include_str!("../test/config.air"), "test/config.air"),
+        (include_str!("../test/context.air"), "test/context.air"),
+        (include_str!("../test/control.air"), "test/control.air"),
+        (include_str!("../test/core.air"), "test/core.air"),
+        (include_str!("../test/debug.air"), "test/debug.air"),
+        (include_str!("../test/decimal.air"), "test/decimal.air"),
+        (include_str!("../test/doc.air"), "test/doc.air"),
+        (include_str!("../test/error.air"), "test/error.air"),
+        (include_str!("../test/function.air"), "test/function.air"),
+        (include_str!("../test/hash.air"), "test/hash.air"),
+        (include_str!("../test/integer.air"), "test/integer.air"),
+        (include_str!("../test/key.air"), "test/key.air"),
+        (include_str!("../test/language.air"), "test/language.air"),
+        (include_str!("../test/link.air"), "test/link.air"),
+        (include_str!("../test/list.air"), "test/list.air"),
+        (include_str!("../test/map.air"), "test/map.air"),
+        (include_str!("../test/pair.air"), "test/pair.air"),
+        (include_str!("../test/rational.air"), "test/rational.air"),
+        (include_str!("../test/regex.air"), "test/regex.air"),
+        (include_str!("../test/regex_group_name.air"), "test/regex_group_name.air"),
+        (include_str!("../test/resource.air"), "test/resource.air"),
+        (include_str!("../test/text.air"), "test/text.air"),
+        (include_str!("../test/unit.air"), "test/unit.air"),
+        (include_str!("../test/value.air"), "test/value.air"),
+    ];
+    let ctx = FmtCtx::default();
+    for (src, file_name) in files {
+        for [title, input, _] in parse_test_file::<3>(src, file_name) {
+            let formatted = input.parse::<Cst>()?.format(ctx);
+            let again = formatted.parse::<Cst>()?.format(ctx);
+            assert_eq!(again, formatted, "file {file_name} case ({title}): format again");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_generate_ctx() -> Result<(), Box<dyn Error>> {
+    let repr: Repr = "{b : [1, 2, 3], a : f : {x : long_value, y : [z]}, c : a + b}".parse()?;
+    let ctx = FmtCtx::default().indent(2).sort_keys(true);
+    let expected = "{ a : f : { x : long_value, y : [z], }, b : [ 1, 2, 3, ], c : a + b, }";
+    assert_eq!(format!("{:#}", ctx.width(80).display(&repr)), expected, "fit");
+    let ctx = ctx.trailing_separator(TrailingSeparator::Never);
+    let expected = "{ a : f : { x : long_value, y : [z] }, b : [ 1, 2, 3 ], c : _ + a : b }";
+    assert_eq!(format!("{}", ctx.display(&repr)), expected, "compact");
+    let expected = "{\n  a : f : {\n    x : long_value,\n    y : [z]\n  },\n  \
+        b : [ 1, 2, 3 ],\n  c : a + b\n}";
+    assert_eq!(format!("{:#}", ctx.width(20).display(&repr)), expected, "break");
+    let ctx = ctx.trailing_separator(TrailingSeparator::Vertical);
+    let expected = "{\n  a : f : {\n    x : long_value,\n    y : [z],\n  },\n  \
+        b : [ 1, 2, 3 ],\n  c : a + b,\n}";
+    assert_eq!(format!("{:#}", ctx.width(30).display(&repr)), expected, "vertical separator");
+
+    let repr: Repr = "[aaaaaaaa + bbbbbbbbbb, long_function_name cccccccccc dddddddddd]".parse()?;
+    let expected = "[\n    aaaaaaaa + bbbbbbbbbb,\n    \
+        long_function_name\n        cccccccccc dddddddddd,\n]";
+    assert_eq!(format!("{:#}", FmtCtx::default().width(30).display(&repr)), expected, "call");
+    Ok(())
+}
+
 #[test]
 fn test_parse_unit() -> Result<(), Box<dyn Error>> {
     test_parse(include_str!("test/unit.air"), "test/unit.air", unit::expected)
@@ -305,11 +586,173 @@ fn test_parse_illegal_example() -> Result<(), Box<dyn Error>> {
     test_parse_illegal(include_str!("test/illegal.air"), "test/illegal.air")
 }
 
+// non-ascii keys are illegal unless the unicode-key feature is on
+#[test]
+fn test_parse_unicode_key_example() -> Result<(), Box<dyn Error>> {
+    let src = include_str!("test/unicode_key.air");
+    if cfg!(feature = "unicode-key") {
+        for [title, s] in parse_test_file::<2>(src, "test/unicode_key.air") {
+            assert!(s.parse::<Repr>().is_ok(), "case ({title}) src({s}): should parse");
+        }
+        return Ok(());
+    }
+    test_parse_illegal(src, "test/unicode_key.air")
+}
+
 #[test]
 fn test_parse_bad_example() -> Result<(), Box<dyn Error>> {
     test_parse_bad(include_str!("test/bad.air"), "test/bad.air")
 }
 
+#[test]
+fn test_parse_error_location() {
+    let cases = [
+        ("[1, 2", 5, 1, 6, "list"),
+        ("{a : 1,\n b : [1, 2 \"x]}", 20, 2, 13, "text"),
+        ("{a : 1, a : 2}", 9, 1, 10, "map"),
+    ];
+    for (s, offset, line, column, label) in cases {
+        let e = s.parse::<Repr>().expect_err("should not parse");
+        assert_eq!(e.offset(), offset, "src({s}): offset");
+        assert_eq!(e.line(), line, "src({s}): line");
+        assert_eq!(e.column(), column, "src({s}): column");
+        assert_eq!(e.label(), Some(label), "src({s}): label");
+    }
+    let e = "[1, 2".parse::<Repr>().expect_err("should not parse");
+    assert_eq!(e.expected(), ["`]`"], "expected list");
+    assert_eq!(e.to_string().lines().nth(3), Some("  |      ^"), "caret position");
+}
+
+#[test]
+fn test_parse_recover() -> Result<(), Box<dyn Error>> {
+    let cases = [
+        ("[1, 2 3 4 5, 6]", "[1, ., 6]", vec![11]),
+        ("[a, (b c, d]", "[a, ., d]", vec![8]),
+        ("{a : 1, a : 2, b : [x y, z]}", "{a : 1, b : [., z]}", vec![9, 23]),
+        ("{a : b c, d : 4}", "{a : ., d : 4}", vec![8]),
+        ("[a, b", "[a, b]", vec![5]),
+        ("[1, (, 3, {a : , b : 2}]", "[1, ., 3, {a : ., b : 2}]", vec![5, 15]),
+        ("{a : )}", "{a : .}", vec![5]),
+        ("[\"x]", "[.]", vec![2, 4]),
+        ("[1, \"x]\n, 2]", "[1, ., 2]", vec![8]),
+        ("[\"a^x b\", 2]", "[., 2]", vec![5]),
+        ("{a : 2 ), b : 3}", "{a : 2, b : 3}", vec![7]),
+        ("[{a : 2]", "[{a : 2}]", vec![7]),
+        ("[(a, [b}, c]", "[., [b, c]]", vec![3, 7, 12]),
+        ("_[a (b, c d]", "_[a . c d]", vec![6]),
+        ("_[a b, c]", "_[a b c]", vec![5]),
+        ("_{a 1 a 2 b (c}", "_{a 1 b c}", vec![9, 14]),
+        ("[_[a 'x^q' b], 1]", "[_[a . b], 1]", vec![9]),
+    ];
+    for (s, expected, offsets) in cases {
+        let (repr, errors) = super::parse_recover::<Repr>(s);
+        let expected: Repr = expected.parse()?;
+        assert_eq!(repr, expected, "src({s}): recovered repr");
+        let real_offsets: Vec<usize> = errors.iter().map(super::ParseError::offset).collect();
+        assert_eq!(real_offsets, offsets, "src({s}): error offsets");
+    }
+    let (repr, errors) = super::parse_recover::<Repr>("[a, b]");
+    assert!(errors.is_empty(), "no errors");
+    assert_eq!(repr, "[a, b]".parse()?, "valid src");
+    Ok(())
+}
+
+#[test]
+fn test_unicode_key() -> Result<(), Box<dyn Error>> {
+    let src = "{名前 : 1, 'Straße 1' : 2, a : 'é'}";
+    let json = r#"{"名前" : 1}"#;
+    if cfg!(feature = "unicode-key") {
+        let repr: Repr = src.parse()?;
+        let expected = Repr::Map(Map::from_iter([
+            (Key::from_str_unchecked("名前"), int("1", 10)),
+            (Key::from_str_unchecked("Straße 1"), int("2", 10)),
+            (Key::from_str_unchecked("a"), key("é")),
+        ]));
+        assert_eq!(repr, expected, "parse unicode keys");
+        let generated = format!("{repr}");
+        assert_eq!(generated, "{ 名前 : 1, 'Straße 1' : 2, a : é, }", "generate unicode keys");
+        assert_eq!(generated.parse::<Repr>()?, repr, "round trip unicode keys");
+        assert!(super::parse_json::<Repr>(json).is_ok(), "json unicode key");
+    } else {
+        assert!(src.parse::<Repr>().is_err(), "unicode keys are opt-in");
+        assert!("'é'".parse::<Repr>().is_err(), "unicode quoted keys are opt-in");
+        assert!(super::parse_json::<Repr>(json).is_err(), "json unicode key is opt-in");
+    }
+    assert!("'a^n'".parse::<Repr>().is_err(), "no control chars in keys");
+    Ok(())
+}
+
+#[test]
+fn test_json() -> Result<(), Box<dyn Error>> {
+    let cases = [
+        ("null", "."),
+        ("[true, false]", "[true, false]"),
+        (r#""a\"\\\n\u00e9\ud83d\ude00""#, "\"a^'\\^n\u{e9}\u{1f600}\""),
+        ("123456789012345678901234567890", "123456789012345678901234567890"),
+        ("-0.5", "0-0.5"),
+        ("1e3", "0E3*1."),
+        (r#"{"a" : {"b" : []}}"#, "{a : {b : []}}"),
+        (r#"{"$key" : "a b"}"#, "'a b'"),
+        (r#"{"$byte" : "00ff"}"#, "byte'X00ff'"),
+        (r#"{"$cell" : 1}"#, ".(1)"),
+        (r#"{"$pair" : ["a", 1]}"#, "\"a\" : 1"),
+        (r#"{"$call" : [{"$key" : "f"}, null]}"#, "_ f ."),
+        (r#"{"$map" : {"$key" : 1}}"#, "{'$key' : 1}"),
+        (r#"{"$key" : "a", "x" : 1}"#, "{'$key' : \"a\", x : 1}"),
+        (r#"{"$cell" : {"$key" : "a"}, "$pair" : null}"#, "{'$cell' : a, '$pair' : .}"),
+    ];
+    for (json, air) in cases {
+        let repr: Repr = super::parse_json(json)?;
+        let expected: Repr = air.parse()?;
+        assert_eq!(repr, expected, "parse json {json}");
+        let generated = super::generate_json(&repr);
+        let repr2: Repr = super::parse_json(&generated)?;
+        assert_eq!(repr, repr2, "round trip {generated}");
+    }
+    assert_eq!(super::generate_json(&"decimal'3.'".parse()?), "3e0", "decimal stays decimal");
+    let illegal = [
+        "", "[1,]", "01", "1.", "{\"a\" : 1,}", "{\"$byte\" : \"zz\"}",
+        "{\"\\n\" : 1}", "\"\n\"", "[1] 2",
+    ];
+    for json in illegal {
+        assert!(super::parse_json::<Repr>(json).is_err(), "illegal json {json}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_binary() -> Result<(), Box<dyn Error>> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut count = 0;
+    while count < 1000 {
+        let val = Val::any(&mut rng, 0);
+        // only data can be encoded
+        let Ok(repr) = Repr::try_from(val.clone()) else {
+            continue;
+        };
+        count += 1;
+        let bytes = super::encode_binary(&repr);
+        let val2: Val = super::decode_binary(&bytes)?;
+        assert_eq!(val, val2, "binary round trip of {val}");
+        for len in 0 .. bytes.len() {
+            let truncated = super::decode_binary::<Repr>(&bytes[.. len]);
+            assert!(truncated.is_err(), "truncated binary of {val} at {len}");
+        }
+    }
+    let repr: Repr = "{a : [a, b, a], b : a}".parse()?;
+    let bytes = super::encode_binary(&repr);
+    assert_eq!(bytes.windows(1).filter(|w| w == b"a").count(), 2, "keys are interned");
+    assert!(super::decode_binary::<Repr>(b"air\x02\x00\x00").is_err(), "unknown version");
+    assert!(super::decode_binary::<Repr>(b"air\x01\x00\x0d").is_err(), "unknown tag");
+    assert!(super::decode_binary::<Repr>(b"air\x01\x00\x03\x00").is_err(), "no key");
+    let nested = |depth| [&b"air\x01\x00"[..], &vec![8; depth], &[0]].concat();
+    assert!(super::decode_binary::<Repr>(&nested(100)).is_ok(), "nested");
+    assert!(super::decode_binary::<Repr>(&nested(1 << 20)).is_err(), "too deeply nested");
+    let duplicate = super::decode_binary::<Repr>(b"air\x01\x01\x01a\x0c\x02\x00\x00\x00\x00");
+    assert_eq!(duplicate.err().map(|e| e.offset()), Some(11), "duplicate key");
+    Ok(())
+}
+
 #[test]
 fn test_doc() -> Result<(), Box<dyn Error>> {
     test_parse(include_str!("test/doc.air"), "test/doc.air", doc::expected)
@@ -123,6 +123,11 @@ fn test_decimal() -> Result<(), Box<dyn Error>> {
     test(include_str!("test/decimal.air"), "test/decimal.air")
 }
 
+#[test]
+fn test_rational() -> Result<(), Box<dyn Error>> {
+    test(include_str!("test/rational.air"), "test/rational.air")
+}
+
 #[test]
 fn test_byte() -> Result<(), Box<dyn Error>> {
     test(include_str!("test/byte.air"), "test/byte.air")
@@ -198,6 +203,23 @@ fn test_language() -> Result<(), Box<dyn Error>> {
     test(include_str!("test/language.air"), "test/language.air")
 }
 
+#[test]
+fn test_regex() -> Result<(), Box<dyn Error>> {
+    test(include_str!("test/regex.air"), "test/regex.air")
+}
+
+// group names are keys only with the unicode-key feature
+#[test]
+#[cfg(not(feature = "unicode-key"))]
+fn test_regex_group_name() -> Result<(), Box<dyn Error>> {
+    test(include_str!("test/regex_group_name.air"), "test/regex_group_name.air")
+}
+
+#[test]
+fn test_hash() -> Result<(), Box<dyn Error>> {
+    test(include_str!("test/hash.air"), "test/hash.air")
+}
+
 #[test]
 fn test_core() -> Result<(), Box<dyn Error>> {
     test(include_str!("test/core.air"), "test/core.air")
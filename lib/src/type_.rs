@@ -11,6 +11,7 @@ pub use self::key::Key;
 pub use self::list::List;
 pub use self::map::Map;
 pub use self::pair::Pair;
+pub use self::rational::Rational;
 pub use self::text::Text;
 pub use self::unit::Unit;
 
@@ -28,6 +29,8 @@ mod int;
 
 mod decimal;
 
+mod rational;
+
 mod byte;
 
 mod cell;
@@ -6,11 +6,15 @@ use bigdecimal::Context;
 use derive_more::Deref;
 use derive_more::DerefMut;
 use derive_more::From;
+use num_bigint::BigInt;
 use num_bigint::Sign;
 use num_traits::One;
+use num_traits::Signed;
+use num_traits::ToPrimitive;
 use num_traits::Zero;
 
 use crate::type_::Bit;
+use crate::type_::Int;
 
 // todo design
 #[derive(Clone, PartialEq, Eq, Hash, From, Deref, DerefMut)]
@@ -81,6 +85,82 @@ impl Decimal {
         Decimal(output)
     }
 
+    // none if self is negative
+    pub fn square_root(&self, cfg: DecimalConfig) -> Option<Decimal> {
+        let output = self.0.sqrt_with_context(&cfg.into_inner())?;
+        Some(Decimal(output))
+    }
+
+    // none if the exponent of the result is out of range
+    pub fn exp(&self, cfg: DecimalConfig) -> Option<Decimal> {
+        let output = exp(&self.0, cfg.precision.get())?;
+        let output = cfg.into_inner().round_decimal(output);
+        Some(Decimal(output))
+    }
+
+    // none if self is not positive
+    pub fn ln(&self, cfg: DecimalConfig) -> Option<Decimal> {
+        if !self.0.is_positive() {
+            return None;
+        }
+        let output = ln(&self.0, cfg.precision.get());
+        let output = cfg.into_inner().round_decimal(output);
+        Some(Decimal(output))
+    }
+
+    // none if the power is undefined or the exponent is too large
+    pub fn power(&self, exponent: &Decimal, cfg: DecimalConfig) -> Option<Decimal> {
+        if exponent.0.is_integer() {
+            let exponent = exponent.0.to_i64()?;
+            if self.0.is_zero() && exponent < 0 {
+                return None;
+            }
+            let output = self.0.powi_with_context(exponent, &cfg.into_inner());
+            return Some(Decimal(output));
+        }
+        if self.0.is_zero() {
+            return exponent.0.is_positive().then(|| self.clone());
+        }
+        if self.0.is_negative() {
+            return None;
+        }
+        // x ^ y = e ^ (y * ln x), and the error of ln x is scaled by y
+        let scale = exponent.0.abs().with_scale(0).digits();
+        let precision = cfg.precision.get() + scale;
+        let output = exp(&(&exponent.0 * ln(&self.0, precision)), precision)?;
+        let output = cfg.into_inner().round_decimal(output);
+        Some(Decimal(output))
+    }
+
+    pub fn abs(&self) -> Decimal {
+        Decimal(self.0.abs())
+    }
+
+    // keep scale digits after the decimal point
+    pub fn round(&self, scale: i64, mode: RoundingMode) -> Decimal {
+        Decimal(self.0.with_scale_round(scale, mode.into_inner()))
+    }
+
+    pub fn from_int(int: Int, cfg: DecimalConfig) -> Decimal {
+        let output = BigDecimal::from_bigint(int.unwrap(), 0);
+        let output = cfg.into_inner().round_decimal(output);
+        Decimal(output)
+    }
+
+    // none if the result has more than max_bits bits
+    pub fn to_int(&self, mode: RoundingMode, max_bits: u64) -> Option<Int> {
+        let (int, scale) = self.0.as_bigint_and_scale();
+        // 10 ^ n has more than 3 * n bits
+        if scale < 0 {
+            let bits = int.bits().saturating_add(scale.unsigned_abs().saturating_mul(3));
+            if bits > max_bits {
+                return None;
+            }
+        }
+        let (int, _) = self.0.with_scale_round(0, mode.into_inner()).into_bigint_and_scale();
+        Some(Int::new(int))
+    }
+
     pub fn less(&self, other: &Decimal) -> Bit {
         Bit::from(self.0 < other.0)
     }
@@ -107,6 +187,14 @@ impl DecimalConfig {
         Self { precision, rounding_mode }
     }
 
+    pub fn precision(&self) -> NonZeroU64 {
+        self.precision
+    }
+
+    pub fn rounding_mode(&self) -> RoundingMode {
+        self.rounding_mode
+    }
+
     pub(crate) fn into_inner(self) -> Context {
         Context::new(self.precision, self.rounding_mode.into_inner())
     }
@@ -126,6 +214,90 @@ impl RoundingMode {
     }
 }
 
+// extra digits of intermediate results of series
+const GUARD_DIGITS: u64 = 30;
+
+fn working_context(precision: u64) -> Context {
+    let precision = NonZeroU64::new(precision + GUARD_DIGITS).unwrap();
+    Context::new(precision, bigdecimal::RoundingMode::HalfEven)
+}
+
+// inputs of exp are at most 10 ^ MAX_EXP_DIGITS in magnitude,
+// so the scales of the result and its intermediate squares stay far within i64
+const MAX_EXP_DIGITS: i64 = 18;
+
+// e ^ x = (e ^ (x / 2 ^ k)) ^ (2 ^ k)
+fn exp(x: &BigDecimal, precision: u64) -> Option<BigDecimal> {
+    if x.abs() > BigDecimal::new(BigInt::one(), -MAX_EXP_DIGITS) {
+        return None;
+    }
+    let half = BigDecimal::new(BigInt::from(5), 1);
+    let one = BigDecimal::one();
+    let mut x = x.clone();
+    let mut k = 0;
+    while x.abs() >= one {
+        x *= &half;
+        k += 1;
+    }
+    // squaring k times loses about k / 3 digits
+    let ctx = working_context(precision + k / 3);
+    let mut sum = BigDecimal::one();
+    let mut term = BigDecimal::one();
+    for n in 1u64 .. {
+        term = ctx.round_decimal(term * &x / BigDecimal::from(n));
+        let next = ctx.round_decimal(&sum + &term);
+        if next == sum {
+            break;
+        }
+        sum = next;
+    }
+    for _ in 0 .. k {
+        sum = ctx.round_decimal(sum.square());
+    }
+    Some(sum)
+}
+
+// ln (m * 10 ^ e) = ln m + e * ln 10, where 1 <= m < 10
+fn ln(x: &BigDecimal, precision: u64) -> BigDecimal {
+    let (int, scale) = x.as_bigint_and_exponent();
+    let digits = x.digits() as i64 - 1;
+    let e = BigInt::from(digits) - scale;
+    let m = ln_reduced(&BigDecimal::new(int, digits), precision);
+    if e.is_zero() {
+        return m;
+    }
+    // the error of ln 10 is scaled by e
+    let e = BigDecimal::from(e);
+    let ln_10 = ln_reduced(&BigDecimal::from(10), precision + e.digits());
+    m + e * ln_10
+}
+
+// ln x = 2 ^ k * ln (x ^ (1 / 2 ^ k)), and ln x = 2 * atanh ((x - 1) / (x + 1))
+fn ln_reduced(x: &BigDecimal, precision: u64) -> BigDecimal {
+    let ctx = working_context(precision);
+    let one = BigDecimal::one();
+    let bound = BigDecimal::new(BigInt::from(1), 1);
+    let mut x = x.clone();
+    let mut k = 0u32;
+    while (&x - &one).abs() >= bound {
+        x = x.sqrt_with_context(&ctx).unwrap();
+        k += 1;
+    }
+    let y = ctx.round_decimal((&x - &one) / (&x + &one));
+    let y2 = ctx.round_decimal(y.square());
+    let mut sum = y.clone();
+    let mut term = y;
+    for n in (3u64 ..).step_by(2) {
+        term = ctx.round_decimal(term * &y2);
+        let next = ctx.round_decimal(&sum + &term / BigDecimal::from(n));
+        if next == sum {
+            break;
+        }
+        sum = next;
+    }
+    sum * BigDecimal::from(BigInt::from(2).pow(k + 1))
+}
+
 impl Neg for Decimal {
     type Output = Decimal;
     fn neg(self) -> Self::Output {
@@ -1,8 +1,13 @@
 use std::ops::Add;
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::BitXor;
 use std::ops::Div;
 use std::ops::Mul;
 use std::ops::Neg;
 use std::ops::Rem;
+use std::ops::Shl;
+use std::ops::Shr;
 use std::ops::Sub;
 
 use derive_more::Deref;
@@ -10,6 +15,7 @@ use derive_more::DerefMut;
 use derive_more::From;
 use num_bigint::BigInt;
 use num_integer::Integer;
+use num_traits::Signed;
 
 use crate::type_::bit::Bit;
 
@@ -52,6 +58,62 @@ impl Int {
         (Int(quotient), Int(rem))
     }
 
+    pub fn power(self, exponent: u32) -> Int {
+        Int(self.0.pow(exponent))
+    }
+
+    // exponent should be non-negative and modulus should be non-zero
+    pub fn modular_power(&self, exponent: &Int, modulus: &Int) -> Int {
+        Int(self.0.modpow(&exponent.0, &modulus.0))
+    }
+
+    pub fn abs(&self) -> Int {
+        Int(self.0.abs())
+    }
+
+    pub fn gcd(&self, other: &Int) -> Int {
+        Int(self.0.gcd(&other.0))
+    }
+
+    pub fn lcm(&self, other: &Int) -> Int {
+        Int(self.0.lcm(&other.0))
+    }
+
+    // self should be non-negative
+    pub fn square_root(&self) -> Int {
+        Int(self.0.sqrt())
+    }
+
+    pub fn min(self, other: Int) -> Int {
+        Int(self.0.min(other.0))
+    }
+
+    pub fn max(self, other: Int) -> Int {
+        Int(self.0.max(other.0))
+    }
+
+    pub fn shift_left(self, n: usize) -> Int {
+        Int(self.0.shl(n))
+    }
+
+    // rounds toward negative infinity
+    pub fn shift_right(self, n: usize) -> Int {
+        Int(self.0.shr(n))
+    }
+
+    // bitwise operations in two's complement
+    pub fn and(self, other: Int) -> Int {
+        Int(self.0.bitand(other.0))
+    }
+
+    pub fn or(self, other: Int) -> Int {
+        Int(self.0.bitor(other.0))
+    }
+
+    pub fn xor(self, other: Int) -> Int {
+        Int(self.0.bitxor(other.0))
+    }
+
     pub fn less(&self, other: &Int) -> Bit {
         Bit::from(self.0.lt(&other.0))
     }
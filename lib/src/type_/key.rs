@@ -19,7 +19,12 @@ impl Key {
         Key(Atom::from(s))
     }
 
+    // printable ascii chars, or with the `unicode-key` feature,
+    // any char which is neither a control char nor a whitespace other than ' '
     pub(crate) fn is_key(c: char) -> bool {
+        if cfg!(feature = "unicode-key") {
+            return c == Self::MIN || !(c.is_control() || c.is_whitespace());
+        }
         Self::MIN <= c && c <= Self::MAX
     }
 }
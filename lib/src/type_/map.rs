@@ -1,19 +1,21 @@
-use std::collections::hash_map::IntoKeys;
-use std::collections::hash_map::IntoValues;
 use std::hash::Hash;
 
 use derive_more::Deref;
 use derive_more::DerefMut;
 use derive_more::IntoIterator;
-use rustc_hash::FxHashMap;
+use indexmap::IndexMap;
+use indexmap::map::IntoKeys;
+use indexmap::map::IntoValues;
+use rustc_hash::FxBuildHasher;
 
+// entries are kept in the order of insertion, so iteration and generation are reproducible
 #[derive(Clone, IntoIterator, Deref, DerefMut)]
 #[into_iterator(owned, ref, ref_mut)]
-pub struct Map<K, V>(FxHashMap<K, V>);
+pub struct Map<K, V>(IndexMap<K, V, FxBuildHasher>);
 
 impl<K, V> Map<K, V> {
     pub(crate) fn with_capacity(len: usize) -> Self {
-        Map(FxHashMap::with_capacity_and_hasher(len, Default::default()))
+        Map(IndexMap::with_capacity_and_hasher(len, FxBuildHasher))
     }
 
     pub(crate) fn into_keys(self) -> IntoKeys<K, V> {
@@ -27,7 +29,7 @@ impl<K, V> Map<K, V> {
 
 impl<K: Eq + Hash, V> FromIterator<(K, V)> for Map<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        Map(FxHashMap::from_iter(iter))
+        Map(IndexMap::from_iter(iter))
     }
 }
 
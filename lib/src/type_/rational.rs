@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+
+use bigdecimal::BigDecimal;
+use derive_more::Deref;
+use derive_more::From;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::Signed;
+use num_traits::Zero;
+
+use crate::type_::Bit;
+use crate::type_::Decimal;
+use crate::type_::DecimalConfig;
+use crate::type_::Int;
+use crate::type_::RoundingMode;
+
+// powers of ten larger than this are rejected instead of allocated
+const MAX_SCALE: u32 = 1 << 20;
+
+// always normalized, the denominator is positive and coprime with the numerator
+#[derive(Clone, PartialEq, Eq, Hash, From, Deref)]
+pub struct Rational(BigRational);
+
+impl Rational {
+    // none if the denominator is zero
+    pub fn new(numerator: Int, denominator: Int) -> Option<Rational> {
+        if denominator.is_zero() {
+            return None;
+        }
+        Some(Rational(BigRational::new(numerator.unwrap(), denominator.unwrap())))
+    }
+
+    pub fn from_int(int: Int) -> Rational {
+        Rational(BigRational::from_integer(int.unwrap()))
+    }
+
+    // exact, none if the scale is too large
+    pub fn from_decimal(decimal: &Decimal) -> Option<Rational> {
+        let (digits, scale) = decimal.as_bigint_and_scale();
+        let digits = digits.into_owned();
+        let power = power_of_ten(scale.unsigned_abs())?;
+        if scale >= 0 {
+            Some(Rational(BigRational::new(digits, power)))
+        } else {
+            Some(Rational(BigRational::from_integer(digits * power)))
+        }
+    }
+
+    pub fn numerator(&self) -> Int {
+        Int::new(self.0.numer().clone())
+    }
+
+    pub fn denominator(&self) -> Int {
+        Int::new(self.0.denom().clone())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn to_int(&self, mode: RoundingMode) -> Int {
+        let (numerator, denominator) = (self.0.numer(), self.0.denom());
+        let (quotient, remainder) = numerator.div_rem(denominator);
+        if remainder.is_zero() {
+            return Int::new(quotient);
+        }
+        let away = &quotient + numerator.signum();
+        let half = (remainder.abs() * 2u8).cmp(denominator);
+        let away_from_zero = match mode {
+            RoundingMode::Infinity => true,
+            RoundingMode::Zero => false,
+            RoundingMode::Positive => numerator.is_positive(),
+            RoundingMode::Negative => numerator.is_negative(),
+            RoundingMode::HalfInfinity => half != Ordering::Less,
+            RoundingMode::HalfZero => half == Ordering::Greater,
+            RoundingMode::HalfEven => match half {
+                Ordering::Less => false,
+                Ordering::Equal => quotient.is_odd(),
+                Ordering::Greater => true,
+            },
+        };
+        Int::new(if away_from_zero { away } else { quotient })
+    }
+
+    // none if the precision is too large
+    pub fn to_decimal(&self, cfg: DecimalConfig) -> Option<Decimal> {
+        let (numerator, denominator) = (self.0.numer(), self.0.denom());
+        if numerator.is_zero() {
+            return Some(Decimal::new(BigDecimal::zero()));
+        }
+        // at least one more digit than the precision
+        let digits = |i: &BigInt| i.magnitude().to_string().len() as i64;
+        let precision = i64::try_from(cfg.precision().get()).ok()?;
+        let scale = precision.saturating_add(digits(denominator) - digits(numerator) + 1);
+        let scale = scale.max(0);
+        let shifted = numerator * power_of_ten(scale.unsigned_abs())?;
+        let (mut quotient, remainder) = shifted.div_rem(denominator);
+        let mut scale = scale;
+        // a sticky digit so that an inexact quotient is never rounded as an exact half
+        if !remainder.is_zero() {
+            quotient = quotient * 10 + numerator.signum();
+            scale += 1;
+        }
+        let output = cfg.into_inner().round_decimal(BigDecimal::new(quotient, scale));
+        Some(Decimal::new(output))
+    }
+
+    #[expect(clippy::should_implement_trait)]
+    pub fn add(self, other: Rational) -> Rational {
+        Rational(self.0 + other.0)
+    }
+
+    pub fn subtract(self, other: Rational) -> Rational {
+        Rational(self.0 - other.0)
+    }
+
+    pub fn multiply(self, other: Rational) -> Rational {
+        Rational(self.0 * other.0)
+    }
+
+    // other should be non-zero
+    pub fn divide(self, other: Rational) -> Rational {
+        Rational(self.0 / other.0)
+    }
+
+    pub fn less(&self, other: &Rational) -> Bit {
+        Bit::from(self.0 < other.0)
+    }
+
+    pub fn less_equal(&self, other: &Rational) -> Bit {
+        Bit::from(self.0 <= other.0)
+    }
+
+    pub fn greater(&self, other: &Rational) -> Bit {
+        Bit::from(self.0 > other.0)
+    }
+
+    pub fn greater_equal(&self, other: &Rational) -> Bit {
+        Bit::from(self.0 >= other.0)
+    }
+
+    pub fn less_greater(&self, other: &Rational) -> Bit {
+        Bit::from(self.0 != other.0)
+    }
+}
+
+fn power_of_ten(scale: u64) -> Option<BigInt> {
+    let scale = u32::try_from(scale).ok().filter(|scale| *scale <= MAX_SCALE)?;
+    Some(BigInt::from(10).pow(scale))
+}
@@ -5,6 +5,8 @@ use derive_more::DerefMut;
 use derive_more::From;
 use derive_more::Into;
 
+use crate::type_::Bit;
+
 #[derive(Clone, Default, PartialEq, Eq, Hash, From, Into, Deref, DerefMut)]
 #[from(&str, String)]
 pub struct Text(String);
@@ -14,3 +16,26 @@ impl Borrow<str> for Text {
         self
     }
 }
+
+// ordered by unicode scalar values
+impl Text {
+    pub fn less(&self, other: &Text) -> Bit {
+        Bit::from(self.0.lt(&other.0))
+    }
+
+    pub fn less_equal(&self, other: &Text) -> Bit {
+        Bit::from(self.0.le(&other.0))
+    }
+
+    pub fn greater(&self, other: &Text) -> Bit {
+        Bit::from(self.0.gt(&other.0))
+    }
+
+    pub fn greater_equal(&self, other: &Text) -> Bit {
+        Bit::from(self.0.ge(&other.0))
+    }
+
+    pub fn less_greater(&self, other: &Text) -> Bit {
+        Bit::from(self.0.ne(&other.0))
+    }
+}
@@ -32,3 +32,48 @@ pub(crate) fn u8_array_to_hex_string_mut(bytes: &[u8], s: &mut String) {
         write!(s, "{b:02x}").unwrap();
     }
 }
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// standard alphabet with padding
+pub(crate) fn u8_array_to_base64_string(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0 .. 4 {
+            if i <= chunk.len() {
+                s.push(char::from(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+// standard alphabet with padding, none if invalid
+pub(crate) fn base64_str_to_vec_u8(s: &str) -> Option<Vec<u8>> {
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+    for (index, chunk) in s.chunks(4).enumerate() {
+        let last = index == s.len() / 4 - 1;
+        let padding = if last { chunk.iter().rev().take_while(|&&c| c == b'=').count() } else { 0 };
+        if padding > 2 {
+            return None;
+        }
+        let mut n = 0u32;
+        for &c in &chunk[.. 4 - padding] {
+            let digit = BASE64_ALPHABET.iter().position(|&a| a == c)?;
+            n = n << 6 | digit as u32;
+        }
+        n <<= 6 * padding;
+        let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        bytes.extend_from_slice(&decoded[.. 3 - padding]);
+    }
+    Some(bytes)
+}